@@ -0,0 +1,697 @@
+//! Parses the RIFF container of a Cubase project along with the tree of archived objects stored
+//! within it.
+//!
+//! A project consists of pairs of chunks, where a "ROOT" chunk names a section of the project (such
+//! as its version or arrangement) and the following "ARCH" chunk contains the archived objects of
+//! that section. Each object may contain further objects along with attribute lists which hold
+//! most of its settings.
+//!
+//! ```
+//! use std::fs;
+//!
+//! use cubase_project_plugins::Archive;
+//!
+//! let project_bytes = fs::read("testdata/Example Project (Cubase 14).cpr")?;
+//! let archive = Archive::parse(&project_bytes)?;
+//!
+//! assert!(archive.sections.iter().any(|section| section.name == "Version"));
+//! assert!(archive.objects().any(|object| object.class == "MTrackList"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    slice,
+};
+
+use crate::{
+    attributes::{self, Attributes, MAX_DEPTH},
+    reader::Error,
+};
+
+const ROOT_CHUNK_ID: &[u8] = b"ROOT";
+const ARCH_CHUNK_ID: &[u8] = b"ARCH";
+
+/// Marks the declaration of a class which is followed by the declarations of its subclasses.
+const BASE_CLASS_DECLARATION: u32 = 0xFFFF_FFFE;
+/// Marks the declaration of the class of an object.
+const CLASS_DECLARATION: u32 = 0xFFFF_FFFF;
+/// Marks a reference to a class which was declared earlier in the same chunk.
+const CLASS_REFERENCE: u32 = 0x8000_0000;
+
+/// The longest class name which is considered valid when searching for objects.
+const MAX_CLASS_NAME_LENGTH: usize = 128;
+
+/// The variant of the RIFF container used by a project.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Format {
+    /// The original RIFF container which uses 32-bit sizes.
+    Riff,
+    /// The RIF2 container which uses 64-bit sizes.
+    Rif2,
+}
+
+impl Format {
+    const fn size_length(self) -> usize {
+        match self {
+            Self::Riff => 4,
+            Self::Rif2 => 8,
+        }
+    }
+}
+
+/// The parsed contents of a Cubase project file.
+#[derive(Debug)]
+pub struct Archive<'a> {
+    /// The variant of the RIFF container used by the project.
+    pub format: Format,
+    /// The sections of the project in the order they are stored.
+    pub sections: Vec<Section<'a>>,
+}
+
+/// A named section of a project such as its version, arrangement or devices.
+#[derive(Debug)]
+pub struct Section<'a> {
+    /// Name of the section.
+    pub name: Cow<'a, str>,
+    /// Class of the object which the section contains.
+    pub class: Cow<'a, str>,
+    /// Offset of the data of the section within the project.
+    pub offset: usize,
+    /// Whether the project ends before the section does.
+    pub truncated: bool,
+    /// Objects and attribute lists within the section.
+    pub elements: Vec<Element<'a>>,
+}
+
+/// An object or attribute list found within a section or object.
+#[derive(Debug)]
+pub enum Element<'a> {
+    /// An archived object.
+    Object(Object<'a>),
+    /// An attribute list.
+    Attributes(Attributes<'a>),
+}
+
+/// An archived object of a particular class.
+#[derive(Debug)]
+pub struct Object<'a> {
+    /// Name of the class of the object.
+    pub class: Cow<'a, str>,
+    /// Version of the class of the object.
+    pub version: u16,
+    /// Offset of the object within the project.
+    pub offset: usize,
+    /// Offset of the payload of the object within the project.
+    pub payload_offset: usize,
+    /// The payload of the object, which contains its fields along with any nested elements.
+    pub payload: &'a [u8],
+    /// Whether the project ends before the object does.
+    pub truncated: bool,
+    /// Objects and attribute lists within the payload of the object.
+    pub elements: Vec<Element<'a>>,
+}
+
+impl<'a> Archive<'a> {
+    /// Parses the chunks of a project along with the objects and attribute lists within them.
+    ///
+    /// Projects which are truncated are parsed as far as possible, with each section, object and
+    /// attribute list which is cut short marked as truncated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the project doesn't begin with a RIFF or RIF2 header, or if its objects
+    /// or attribute lists are nested deeper than is considered valid.
+    pub fn parse(project_bytes: &'a [u8]) -> Result<Self, Error> {
+        let format = match project_bytes.get(0..4) {
+            Some(b"RIFF") => Format::Riff,
+            Some(b"RIF2") => Format::Rif2,
+            _ => return Err(Error::UnexpectedHeader),
+        };
+
+        // The header is followed by the size of the container and the form type of the project.
+        let mut index = 4 + format.size_length() + 4;
+        let mut sections = Vec::new();
+        let mut root = None;
+
+        while let Some((chunk_id, start, end, truncated)) = read_chunk(project_bytes, index, format)
+        {
+            match chunk_id {
+                ROOT_CHUNK_ID => root = read_root(&project_bytes[start..end]),
+                ARCH_CHUNK_ID => {
+                    let (name, class) = root.take().unwrap_or_default();
+                    let mut parser = ObjectParser {
+                        project_bytes,
+                        base: start,
+                        format,
+                        classes: HashMap::new(),
+                    };
+                    sections.push(Section {
+                        name,
+                        class,
+                        offset: start,
+                        truncated,
+                        elements: parser.parse_elements(start, end, truncated, 0)?,
+                    });
+                }
+                _ => (),
+            }
+            index = end;
+        }
+
+        Ok(Self { format, sections })
+    }
+
+    /// Iterates through every object in the project, depth first in the order they are stored.
+    #[must_use]
+    pub fn objects(&self) -> Objects<'_, 'a> {
+        Objects {
+            stack: self
+                .sections
+                .iter()
+                .rev()
+                .map(|section| section.elements.iter())
+                .collect(),
+        }
+    }
+}
+
+impl<'a> Object<'a> {
+    /// Iterates through the attribute lists directly within the payload of the object.
+    pub fn attributes(&self) -> impl Iterator<Item = &Attributes<'a>> {
+        self.elements.iter().filter_map(|element| match element {
+            Element::Attributes(attributes) => Some(attributes),
+            Element::Object(_) => None,
+        })
+    }
+
+    /// Iterates through the objects directly within the payload of the object.
+    pub fn children(&self) -> impl Iterator<Item = &Self> {
+        self.elements.iter().filter_map(|element| match element {
+            Element::Object(object) => Some(object),
+            Element::Attributes(_) => None,
+        })
+    }
+
+    /// Iterates through every object nested within the object, depth first in the order they are
+    /// stored.
+    #[must_use]
+    pub fn objects(&self) -> Objects<'_, 'a> {
+        Objects {
+            stack: vec![self.elements.iter()],
+        }
+    }
+}
+
+/// An iterator through every object in a project, depth first in the order they are stored.
+pub struct Objects<'b, 'a> {
+    /// Iterators through the elements of each level of the tree being visited.
+    stack: Vec<slice::Iter<'b, Element<'a>>>,
+}
+
+impl<'b, 'a> Iterator for Objects<'b, 'a> {
+    type Item = &'b Object<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(elements) = self.stack.last_mut() {
+            match elements.next() {
+                Some(Element::Object(object)) => {
+                    self.stack.push(object.elements.iter());
+                    return Some(object);
+                }
+                Some(Element::Attributes(_)) => (),
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Parses the objects within an "ARCH" chunk, keeping track of the classes declared so far so
+/// that later references to them may be resolved.
+struct ObjectParser<'a> {
+    /// Binary Cubase project bytes.
+    project_bytes: &'a [u8],
+    /// Offset of the data of the chunk, which class references are relative to.
+    base: usize,
+    /// The variant of the RIFF container used by the project.
+    format: Format,
+    /// Classes declared so far, keyed by their offset relative to the data of the chunk.
+    classes: HashMap<usize, (Cow<'a, str>, u16)>,
+}
+
+impl<'a> ObjectParser<'a> {
+    /// Parses the elements between the given offsets, where the depth is the number of objects
+    /// which contain them.
+    fn parse_elements(
+        &mut self,
+        start: usize,
+        end: usize,
+        truncated: bool,
+        depth: usize,
+    ) -> Result<Vec<Element<'a>>, Error> {
+        if depth > MAX_DEPTH {
+            return Err(Error::CorruptProject);
+        }
+
+        let mut elements = Vec::new();
+        let mut failed_attributes = HashSet::new();
+        let mut index = start;
+
+        // Object payloads also contain fields other than objects and attribute lists, so each
+        // position is checked in turn until an element is found.
+        while index < end {
+            if let Some(mut object) = self.parse_object(index, end, truncated) {
+                index = object.payload_offset + object.payload.len();
+                object.elements =
+                    self.parse_elements(object.payload_offset, index, object.truncated, depth + 1)?;
+                elements.push(Element::Object(object));
+                continue;
+            }
+
+            if let Some((attributes, updated_index)) = Attributes::find(
+                &self.project_bytes[..end],
+                index,
+                truncated,
+                &mut failed_attributes,
+            )? {
+                index = updated_index;
+                elements.push(Element::Attributes(attributes));
+                continue;
+            }
+
+            index += 1;
+        }
+
+        Ok(elements)
+    }
+
+    /// Parses the object at the given index, leaving the elements within its payload to be parsed
+    /// by the caller.
+    fn parse_object(&mut self, index: usize, end: usize, truncated: bool) -> Option<Object<'a>> {
+        let bytes = &self.project_bytes[..end];
+        let marker = attributes::read_u32(bytes, index)?;
+
+        let mut declarations = Vec::new();
+        let (class, version, size_index) = if marker == BASE_CLASS_DECLARATION
+            || marker == CLASS_DECLARATION
+        {
+            // A class is declared along with the classes it derives from, ending with the
+            // class of the object itself.
+            let mut declaration_index = index;
+            loop {
+                let marker = attributes::read_u32(bytes, declaration_index)?;
+                if marker != BASE_CLASS_DECLARATION && marker != CLASS_DECLARATION {
+                    return None;
+                }
+
+                let (class, version, next_index) = read_class_name(bytes, declaration_index + 4)?;
+                declarations.push((declaration_index - self.base, class, version));
+                declaration_index = next_index;
+
+                if marker == CLASS_DECLARATION {
+                    break;
+                }
+            }
+
+            let (_, class, version) = declarations.last()?.clone();
+            (class, version, declaration_index)
+        } else if marker & CLASS_REFERENCE != 0 {
+            // Classes are declared before they are referenced, which rules out most positions
+            // without looking up the class.
+            let class_offset = usize::try_from(marker & !CLASS_REFERENCE).ok()?;
+            if class_offset >= index - self.base {
+                return None;
+            }
+            let (class, version) = self.classes.get(&class_offset)?.clone();
+            (class, version, index + 4)
+        } else {
+            return None;
+        };
+
+        let size_length = self.format.size_length();
+        let size_bytes = bytes.get(size_index..size_index + size_length)?;
+        let size = usize::try_from(
+            size_bytes
+                .iter()
+                .fold(0_u64, |size, &byte| (size << 8) | u64::from(byte)),
+        )
+        .ok()?;
+
+        let payload_offset = size_index + size_length;
+        let payload_end = payload_offset.checked_add(size)?;
+        let (payload_end, object_truncated) = if payload_end <= end {
+            (payload_end, false)
+        } else if truncated {
+            (end, true)
+        } else {
+            return None;
+        };
+
+        for (class_offset, class, version) in declarations {
+            self.classes.insert(class_offset, (class, version));
+        }
+
+        Some(Object {
+            class,
+            version,
+            offset: index,
+            payload_offset,
+            payload: &self.project_bytes[payload_offset..payload_end],
+            truncated: object_truncated,
+            elements: Vec::new(),
+        })
+    }
+}
+
+/// Reads the ID and bounds of the data of the chunk at the given index, where the data of a chunk
+/// which is cut short ends at the end of the project.
+fn read_chunk(
+    project_bytes: &[u8],
+    index: usize,
+    format: Format,
+) -> Option<(&[u8], usize, usize, bool)> {
+    let chunk_id = project_bytes.get(index..index + 4)?;
+    let size_length = format.size_length();
+    let size_bytes = project_bytes.get(index + 4..index + 4 + size_length)?;
+    let size = size_bytes
+        .iter()
+        .fold(0_u64, |size, &byte| (size << 8) | u64::from(byte));
+
+    let start = index + 4 + size_length;
+    let end = usize::try_from(size)
+        .ok()
+        .and_then(|size| start.checked_add(size))
+        .unwrap_or(usize::MAX);
+    if end > project_bytes.len() {
+        return Some((chunk_id, start, project_bytes.len(), true));
+    }
+    Some((chunk_id, start, end, false))
+}
+
+/// Reads the name of a section and the class of the object it contains from a "ROOT" chunk.
+fn read_root(root_bytes: &[u8]) -> Option<(Cow<'_, str>, Cow<'_, str>)> {
+    let (name, index) = attributes::read_string(root_bytes, 0)?;
+    let (class, _) = attributes::read_string(root_bytes, index)?;
+    Some((name, class))
+}
+
+/// Reads a class name followed by its version, only succeeding when the name is valid.
+fn read_class_name(bytes: &[u8], index: usize) -> Option<(Cow<'_, str>, u16, usize)> {
+    let len = usize::try_from(attributes::read_u32(bytes, index)?).ok()?;
+    if !(2..=MAX_CLASS_NAME_LENGTH).contains(&len) {
+        return None;
+    }
+
+    let name_index = index + 4;
+    let name_bytes = bytes.get(name_index..name_index + len)?;
+    let (&terminator, name_bytes) = name_bytes.split_last()?;
+    if terminator != 0
+        || !name_bytes
+            .iter()
+            .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+    {
+        return None;
+    }
+
+    let version_index = name_index + len;
+    let version = u16::from_be_bytes(
+        bytes
+            .get(version_index..version_index + 2)?
+            .try_into()
+            .ok()?,
+    );
+    Some((
+        String::from_utf8_lossy(name_bytes),
+        version,
+        version_index + 2,
+    ))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use rstest::*;
+    use similar_asserts::assert_eq;
+
+    use super::*;
+    use crate::attributes::Value;
+
+    /// Builds a project containing a single section whose "ARCH" chunk contains the given data.
+    fn build_project(arch_data: &[u8]) -> Vec<u8> {
+        let mut root_data = Vec::new();
+        for string in [&b"Arrangement1\0"[..], b"PArrangement\0"] {
+            root_data.extend(u32::try_from(string.len()).unwrap().to_be_bytes());
+            root_data.extend(string);
+        }
+
+        let mut project_bytes = b"RIFF\0\0\0\0NUND".to_vec();
+        for (chunk_id, data) in [(ROOT_CHUNK_ID, &root_data[..]), (ARCH_CHUNK_ID, arch_data)] {
+            project_bytes.extend(chunk_id);
+            project_bytes.extend(u32::try_from(data.len()).unwrap().to_be_bytes());
+            project_bytes.extend(data);
+        }
+        project_bytes
+    }
+
+    /// Builds an attribute list containing a single attribute with the given value.
+    fn build_attributes(key: &[u8], value: &[u8]) -> Vec<u8> {
+        let mut bytes = 1_u32.to_be_bytes().to_vec();
+        bytes.extend(u32::try_from(key.len()).unwrap().to_be_bytes());
+        bytes.extend(key);
+        bytes.extend(value);
+        bytes
+    }
+
+    /// Builds the given number of attribute lists, each nested within the one before it.
+    fn build_nested_attributes(depth: usize) -> Vec<u8> {
+        let integer = [&1_u16.to_be_bytes()[..], &42_i64.to_be_bytes()].concat();
+        (1..depth).fold(build_attributes(b"Value\0", &integer), |bytes, _| {
+            let container = [&2_u16.to_be_bytes()[..], &6_u16.to_be_bytes(), &bytes].concat();
+            build_attributes(b"Nested\0", &container)
+        })
+    }
+
+    /// Builds the given number of objects, each nested within the payload of the one before it.
+    fn build_nested_objects(depth: usize) -> Vec<u8> {
+        (0..depth).fold(Vec::new(), |payload, _| {
+            let mut bytes = CLASS_DECLARATION.to_be_bytes().to_vec();
+            bytes.extend(7_u32.to_be_bytes());
+            bytes.extend(b"MEvent\0");
+            bytes.extend(1_u16.to_be_bytes());
+            bytes.extend(u32::try_from(payload.len()).unwrap().to_be_bytes());
+            bytes.extend(payload);
+            bytes
+        })
+    }
+
+    #[rstest]
+    #[case::cubase_45_32_bit(
+        "Example Project (Cubase 4.5 32-bit).cpr",
+        vec![
+            ("Version", "PAppVersion"),
+            ("Arrangement1", "PArrangement"),
+            ("Devices", "FAttributes"),
+            ("WindowLayouts", "UWindowLayout"),
+            ("ProjectLayouts", "FAttributes"),
+        ],
+    )]
+    #[case::cubase_14(
+        "Example Project (Cubase 14).cpr",
+        vec![
+            ("Version", "PAppVersion"),
+            ("Arrangement1", "PArrangement"),
+            ("ComputerGuid", "CmString"),
+            ("Devices", "FAttributes"),
+            ("WindowLayouts", "UWindowLayout"),
+            ("ProjectLayouts", "FAttributes"),
+            ("Metadata", "StMedia::PAttributes"),
+        ],
+    )]
+    #[case::cubase_sx3(
+        "Example Project (Cubase SX3).cpr",
+        vec![
+            ("Arrangement1", "PArrangement"),
+            ("Devices", "FMemoryStream"),
+            ("WindowLayouts", "UWindowLayout"),
+            ("ProjectLayouts", "FAttributes"),
+            ("Version", "PAppVersion"),
+        ],
+    )]
+    fn test_parse_sections(#[case] filename: &str, #[case] expected_sections: Vec<(&str, &str)>) {
+        let project_path = PathBuf::from("testdata").join(filename);
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let archive = Archive::parse(&project_bytes).unwrap();
+
+        let actual_sections = archive
+            .sections
+            .iter()
+            .map(|section| (section.name.as_ref(), section.class.as_ref()))
+            .collect::<Vec<_>>();
+        assert_eq!(archive.format, Format::Riff);
+        assert_eq!(actual_sections, expected_sections);
+        assert!(archive.sections.iter().all(|section| !section.truncated));
+    }
+
+    #[rstest]
+    #[case::cubase_45_32_bit("Example Project (Cubase 4.5 32-bit).cpr")]
+    #[case::cubase_85_64_bit("Example Project (Cubase 8.5 64-bit).cpr")]
+    #[case::cubase_14("Example Project (Cubase 14).cpr")]
+    fn test_parse_objects(#[case] filename: &str) {
+        let project_path = PathBuf::from("testdata").join(filename);
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let archive = Archive::parse(&project_bytes).unwrap();
+
+        // The arrangement contains the track list, which contains the instrument tracks.
+        let arrangement = archive
+            .objects()
+            .find(|object| object.class == "PArrangement")
+            .unwrap();
+        let track_list = arrangement
+            .elements
+            .iter()
+            .find_map(|element| match element {
+                Element::Object(root) if root.class == "MRoot" => {
+                    root.elements.iter().find_map(|element| match element {
+                        Element::Object(object) if object.class == "MTrackList" => Some(object),
+                        _ => None,
+                    })
+                }
+                _ => None,
+            })
+            .unwrap();
+        let instrument_tracks = track_list
+            .elements
+            .iter()
+            .filter(|element| {
+                matches!(element, Element::Object(object) if object.class == "MInstrumentTrackEvent")
+            })
+            .count();
+        assert_eq!(instrument_tracks, 2);
+
+        for object in archive.objects() {
+            assert_eq!(
+                &project_bytes[object.payload_offset..object.payload_offset + object.payload.len()],
+                object.payload
+            );
+            assert!(!object.truncated);
+        }
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        let project_path = PathBuf::from("testdata").join("Example Project (Cubase 14).cpr");
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let archive = Archive::parse(&project_bytes).unwrap();
+
+        let devices = archive
+            .sections
+            .iter()
+            .find(|section| section.name == "Devices")
+            .unwrap();
+        let attributes = devices
+            .elements
+            .iter()
+            .find_map(|element| match element {
+                Element::Object(object) => object.elements.first(),
+                Element::Attributes(_) => None,
+            })
+            .and_then(|element| match element {
+                Element::Attributes(attributes) => Some(attributes),
+                Element::Object(_) => None,
+            })
+            .unwrap();
+
+        let mixer = attributes.get_attributes("VST Mixer").unwrap();
+        let Some(Value::List(outputs)) = mixer.get("Output Channels") else {
+            unreachable!("the mixer doesn't contain a list of output channels");
+        };
+        let output_names = outputs
+            .iter()
+            .map(|output| {
+                output
+                    .get_attributes("Name")
+                    .unwrap()
+                    .get_str("String")
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(output_names, vec!["Stereo Out"]);
+        assert!(!attributes.truncated);
+    }
+
+    #[test]
+    fn test_parse_truncated() {
+        let project_path = PathBuf::from("testdata").join("Truncated Project (Plugin GUID).cpr");
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let archive = Archive::parse(&project_bytes).unwrap();
+
+        let sections = archive
+            .sections
+            .iter()
+            .map(|section| (section.name.as_ref(), section.truncated))
+            .collect::<Vec<_>>();
+        assert_eq!(sections, vec![("Version", false), ("Arrangement1", true)]);
+    }
+
+    #[test]
+    fn test_parse_within_invalid_attributes() {
+        // The second attribute of the outer list has a key which is too short, so only the list
+        // nested within it is valid.
+        let inner = build_nested_attributes(1);
+        let container = [&2_u16.to_be_bytes()[..], &6_u16.to_be_bytes(), &inner].concat();
+        let mut outer = build_attributes(b"Nested\0", &container);
+        outer[..4].copy_from_slice(&2_u32.to_be_bytes());
+        outer.extend(1_u32.to_be_bytes());
+        outer.extend(b"\0");
+        let project_bytes = build_project(&outer);
+
+        let archive = Archive::parse(&project_bytes).unwrap();
+
+        let offsets = archive.sections[0]
+            .elements
+            .iter()
+            .map(|element| match element {
+                Element::Attributes(attributes) => attributes.offset,
+                Element::Object(object) => object.offset,
+            })
+            .collect::<Vec<_>>();
+        let inner_offset = project_bytes.len() - 5 - inner.len();
+        assert_eq!(offsets, vec![inner_offset]);
+    }
+
+    #[rstest]
+    #[case::attributes_at_limit(build_nested_attributes(MAX_DEPTH), true)]
+    #[case::attributes_beyond_limit(build_nested_attributes(MAX_DEPTH + 1), false)]
+    #[case::objects_at_limit(build_nested_objects(MAX_DEPTH), true)]
+    #[case::objects_beyond_limit(build_nested_objects(MAX_DEPTH + 1), false)]
+    fn test_parse_nesting_depth(#[case] arch_data: Vec<u8>, #[case] expected_valid: bool) {
+        let project_bytes = build_project(&arch_data);
+
+        let archive = Archive::parse(&project_bytes);
+
+        if expected_valid {
+            assert_eq!(archive.unwrap().sections[0].elements.len(), 1);
+        } else {
+            assert!(matches!(archive, Err(Error::CorruptProject)));
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_header() {
+        let project_bytes = b"POOP";
+
+        let archive = Archive::parse(project_bytes);
+
+        assert!(matches!(archive, Err(Error::UnexpectedHeader)));
+    }
+}
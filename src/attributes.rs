@@ -0,0 +1,539 @@
+//! Parses the typed attribute lists which Cubase uses to store most settings within the objects of
+//! a project, such as the channels of tracks and the plugins loaded on them.
+
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashSet,
+};
+
+use crate::reader::Error;
+
+const TYPE_INTEGER: u16 = 1;
+const TYPE_CONTAINER: u16 = 2;
+const TYPE_FLOAT: u16 = 4;
+const TYPE_STRING: u16 = 8;
+
+const CONTAINER_INTEGERS: u16 = 2;
+const CONTAINER_STRINGS: u16 = 4;
+const CONTAINER_LIST: u16 = 5;
+const CONTAINER_ATTRIBUTES: u16 = 6;
+const CONTAINER_BLOB: u16 = 7;
+const CONTAINER_OBJECT: u16 = 20;
+const CONTAINER_REFERENCE: u16 = 21;
+const CONTAINER_OBJECTS: u16 = 201;
+
+/// The longest key which is considered valid when searching for attribute lists.
+const MAX_KEY_LENGTH: usize = 255;
+
+/// The fewest bytes an attribute may occupy, which is a one character key and a type.
+const MIN_ATTRIBUTE_LENGTH: usize = 4 + 1 + 2;
+
+/// The deepest nesting of attribute lists or objects which is accepted before a project is
+/// considered corrupt, which is well beyond that of any project seen so far.
+pub(crate) const MAX_DEPTH: usize = 64;
+
+/// A list of named attributes, each of which holds a typed value.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Attributes<'a> {
+    /// Offset of the attribute list within the project.
+    pub offset: usize,
+    /// The attributes in the order they are stored.
+    pub entries: Vec<Attribute<'a>>,
+    /// Whether the project ends before the attribute list does.
+    pub truncated: bool,
+}
+
+/// A single named attribute.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Attribute<'a> {
+    /// Name of the attribute.
+    pub key: Cow<'a, str>,
+    /// Offset of the name of the attribute within the project.
+    pub offset: usize,
+    /// Value of the attribute.
+    pub value: Value<'a>,
+}
+
+/// An attribute list which describes an object of a particular class.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ClassAttributes<'a> {
+    /// Name of the class.
+    pub class: Cow<'a, str>,
+    /// Attributes of the object.
+    pub attributes: Attributes<'a>,
+}
+
+/// The value of an attribute.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Value<'a> {
+    /// A signed integer, which is also used for flags.
+    Integer(i64),
+    /// A floating point number.
+    Float(f64),
+    /// A string.
+    String(Cow<'a, str>),
+    /// A nested attribute list.
+    Attributes(Attributes<'a>),
+    /// A list of nested attribute lists.
+    List(Vec<Attributes<'a>>),
+    /// A list of strings.
+    Strings(Vec<Cow<'a, str>>),
+    /// A list of signed integers.
+    Integers(Vec<i64>),
+    /// Binary data such as the state of a plugin.
+    Blob(&'a [u8]),
+    /// An attribute list describing an object of a particular class.
+    Object(ClassAttributes<'a>),
+    /// A list of attribute lists describing objects.
+    Objects(Vec<ClassAttributes<'a>>),
+    /// A reference to another object.
+    Reference(u32, u32),
+    /// A value which was cut short by the end of the project.
+    Truncated,
+}
+
+impl<'a> Attributes<'a> {
+    /// Obtains the value of the first attribute with the given key.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&Value<'a>> {
+        self.entries
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .map(|attribute| &attribute.value)
+    }
+
+    /// Obtains the position of the first attribute with the given key.
+    #[must_use]
+    pub fn position(&self, key: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|attribute| attribute.key == key)
+    }
+
+    /// Obtains the value of the first attribute with the given key if it is an integer.
+    #[must_use]
+    pub fn get_int(&self, key: &str) -> Option<i64> {
+        self.get(key).and_then(Value::as_int)
+    }
+
+    /// Obtains the value of the first attribute with the given key if it is a string.
+    #[must_use]
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    /// Obtains the value of the first attribute with the given key if it is binary data.
+    #[must_use]
+    pub fn get_blob(&self, key: &str) -> Option<&'a [u8]> {
+        self.get(key).and_then(Value::as_blob)
+    }
+
+    /// Obtains the value of the first attribute with the given key if it is an attribute list.
+    #[must_use]
+    pub fn get_attributes(&self, key: &str) -> Option<&Self> {
+        self.get(key).and_then(Value::as_attributes)
+    }
+
+    /// Attempts to parse an attribute list which starts at the given index of the project, only
+    /// succeeding when the entire list is valid.
+    ///
+    /// When the project is known to be truncated, an attribute list which is cut short by the end
+    /// of the project is also accepted.
+    ///
+    /// The offsets of attribute lists which couldn't be parsed are added to the failed offsets, so
+    /// that lists nested within an invalid list are skipped rather than parsed again when searching
+    /// the same bytes from a later index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if attribute lists are nested deeper than is considered valid.
+    pub(crate) fn find(
+        bytes: &'a [u8],
+        index: usize,
+        truncated: bool,
+        failed: &mut HashSet<usize>,
+    ) -> Result<Option<(Self, usize)>, Error> {
+        // Only non-empty lists are searched for as empty lists are indistinguishable from zeros.
+        let Some(count) = read_u32(bytes, index).and_then(|count| usize::try_from(count).ok())
+        else {
+            return Ok(None);
+        };
+        let remaining = bytes.len() - index - 4;
+        if count == 0 || (!truncated && count > remaining / MIN_ATTRIBUTE_LENGTH) {
+            return Ok(None);
+        }
+
+        let parser = Parser {
+            bytes,
+            truncated,
+            depth: Cell::new(0),
+            failed: RefCell::new(failed),
+        };
+        match parser.parse_attributes(index) {
+            Ok((attributes, index)) if !attributes.entries.is_empty() => {
+                Ok(Some((attributes, index)))
+            }
+            Err(Failure::TooDeep) => Err(Error::CorruptProject),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl<'a> Value<'a> {
+    /// Obtains the value as an integer.
+    #[must_use]
+    pub const fn as_int(&self) -> Option<i64> {
+        match self {
+            Self::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Obtains the value as a floating point number.
+    #[must_use]
+    pub const fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Obtains the value as a string.
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Obtains the value as binary data.
+    #[must_use]
+    pub const fn as_blob(&self) -> Option<&'a [u8]> {
+        match self {
+            Self::Blob(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Obtains the value as an attribute list.
+    #[must_use]
+    pub const fn as_attributes(&self) -> Option<&Attributes<'a>> {
+        match self {
+            Self::Attributes(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Whether the value was cut short by the end of the project.
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        match self {
+            Self::Truncated => true,
+            Self::Attributes(attributes) => attributes.truncated,
+            Self::List(items) => items.last().is_some_and(|item| item.truncated),
+            Self::Object(object) => object.attributes.truncated,
+            Self::Objects(objects) => objects
+                .last()
+                .is_some_and(|object| object.attributes.truncated),
+            _ => false,
+        }
+    }
+}
+
+/// The reason an attribute list couldn't be parsed.
+enum Failure {
+    /// The bytes don't contain a valid attribute list.
+    Invalid,
+    /// The end of the bytes was reached part way through the attribute list.
+    Truncated,
+    /// Attribute lists are nested deeper than is considered valid.
+    TooDeep,
+}
+
+type ParseResult<T> = Result<(T, usize), Failure>;
+
+/// Parses attribute lists, where the bytes end at the end of the region being parsed.
+struct Parser<'a, 'b> {
+    /// Bytes of the project up to the end of the region being parsed.
+    bytes: &'a [u8],
+    /// Whether reaching the end of the bytes is expected as the project is truncated.
+    truncated: bool,
+    /// The number of attribute lists which contain the one being parsed.
+    depth: Cell<usize>,
+    /// Offsets of attribute lists which are known to be invalid.
+    failed: RefCell<&'b mut HashSet<usize>>,
+}
+
+impl<'a> Parser<'a, '_> {
+    fn parse_attributes(&self, offset: usize) -> ParseResult<Attributes<'a>> {
+        let depth = self.depth.get();
+        if depth == MAX_DEPTH {
+            return Err(Failure::TooDeep);
+        }
+        if self.failed.borrow().contains(&offset) {
+            return Err(Failure::Invalid);
+        }
+
+        self.depth.set(depth + 1);
+        let result = self.parse_entries(offset);
+        self.depth.set(depth);
+
+        // Lists which aren't nested within another are never reached again from a later index.
+        if depth > 0 && matches!(result, Err(Failure::Invalid | Failure::Truncated)) {
+            self.failed.borrow_mut().insert(offset);
+        }
+        result
+    }
+
+    fn parse_entries(&self, offset: usize) -> ParseResult<Attributes<'a>> {
+        let mut attributes = Attributes {
+            offset,
+            entries: Vec::new(),
+            truncated: false,
+        };
+
+        let (count, mut index) = match self.take_u32(offset) {
+            Ok(count) => count,
+            Err(failure) => return self.truncate(failure, attributes),
+        };
+        let count = usize::try_from(count).map_err(|_| Failure::Invalid)?;
+
+        for _ in 0..count {
+            let (key, value_index) = match self.take_key(index) {
+                Ok(key) => key,
+                Err(failure) => return self.truncate(failure, attributes),
+            };
+
+            let (value, next_index) = match self.take_value(value_index) {
+                Ok(value) => value,
+                Err(Failure::Truncated) if self.truncated => (Value::Truncated, self.bytes.len()),
+                Err(failure) => return Err(failure),
+            };
+
+            // Space for the remaining attributes is only reserved once the first is found to be
+            // valid, as most positions searched don't begin a list.
+            if attributes.entries.is_empty() {
+                let remaining = (self.bytes.len() - next_index) / MIN_ATTRIBUTE_LENGTH;
+                attributes.entries.reserve_exact(count.min(remaining + 1));
+            }
+
+            let value_truncated = value.is_truncated();
+            attributes.entries.push(Attribute {
+                key,
+                offset: index + 4,
+                value,
+            });
+            if value_truncated {
+                attributes.truncated = true;
+                return Ok((attributes, self.bytes.len()));
+            }
+            index = next_index;
+        }
+
+        Ok((attributes, index))
+    }
+
+    /// Accepts a partial attribute list which is cut short by the end of a truncated project.
+    fn truncate(
+        &self,
+        failure: Failure,
+        mut attributes: Attributes<'a>,
+    ) -> ParseResult<Attributes<'a>> {
+        match failure {
+            Failure::Truncated if self.truncated => {
+                attributes.truncated = true;
+                Ok((attributes, self.bytes.len()))
+            }
+            failure => Err(failure),
+        }
+    }
+
+    fn take_value(&self, index: usize) -> ParseResult<Value<'a>> {
+        let (value_type, index) = self.take_u16(index)?;
+        match value_type {
+            TYPE_INTEGER => {
+                let (value, index) = self.take_array(index)?;
+                Ok((Value::Integer(i64::from_be_bytes(value)), index))
+            }
+            TYPE_FLOAT => {
+                let (value, index) = self.take_array(index)?;
+                Ok((Value::Float(f64::from_be_bytes(value)), index))
+            }
+            TYPE_STRING => {
+                let (value, index) = self.take_string(index)?;
+                Ok((Value::String(value), index))
+            }
+            TYPE_CONTAINER => self.take_container(index),
+            _ => Err(Failure::Invalid),
+        }
+    }
+
+    fn take_container(&self, index: usize) -> ParseResult<Value<'a>> {
+        let (container_type, index) = self.take_u16(index)?;
+        match container_type {
+            CONTAINER_ATTRIBUTES => {
+                let (attributes, index) = self.parse_attributes(index)?;
+                Ok((Value::Attributes(attributes), index))
+            }
+            CONTAINER_LIST => {
+                let (items, index) =
+                    self.take_list(index, Self::parse_attributes, |item| item.truncated)?;
+                Ok((Value::List(items), index))
+            }
+            CONTAINER_STRINGS => {
+                let (items, index) = self.take_list(index, Self::take_string, |_| false)?;
+                Ok((Value::Strings(items), index))
+            }
+            CONTAINER_INTEGERS => {
+                let (items, index) = self.take_list(
+                    index,
+                    |parser, index| {
+                        let (value, index) = parser.take_array(index)?;
+                        Ok((i64::from_be_bytes(value), index))
+                    },
+                    |_| false,
+                )?;
+                Ok((Value::Integers(items), index))
+            }
+            CONTAINER_BLOB => {
+                let (len, index) = self.take_u32(index)?;
+                let len = usize::try_from(len).map_err(|_| Failure::Invalid)?;
+                let (blob, index) = self.take(index, len)?;
+                Ok((Value::Blob(blob), index))
+            }
+            CONTAINER_OBJECT => {
+                let (object, index) = self.take_object(index)?;
+                Ok((Value::Object(object), index))
+            }
+            CONTAINER_OBJECTS => {
+                let (objects, index) = self.take_list(
+                    index,
+                    |parser, index| {
+                        let (object_type, index) = parser.take_u16(index)?;
+                        if object_type != CONTAINER_OBJECT {
+                            return Err(Failure::Invalid);
+                        }
+                        parser.take_object(index)
+                    },
+                    |object| object.attributes.truncated,
+                )?;
+                Ok((Value::Objects(objects), index))
+            }
+            CONTAINER_REFERENCE => {
+                let (first, index) = self.take_u32(index)?;
+                let (second, index) = self.take_u32(index)?;
+                Ok((Value::Reference(first, second), index))
+            }
+            _ => Err(Failure::Invalid),
+        }
+    }
+
+    fn take_object(&self, index: usize) -> ParseResult<ClassAttributes<'a>> {
+        let (class, index) = self.take_key(index)?;
+
+        // The purpose of the eight bytes which follow the class name is unknown.
+        let (_, index) = self.take(index, 8)?;
+
+        let (attributes, index) = self.parse_attributes(index)?;
+        Ok((ClassAttributes { class, attributes }, index))
+    }
+
+    /// Takes a list of items, stopping early when an item is cut short by the end of the project.
+    fn take_list<T>(
+        &self,
+        index: usize,
+        take_item: impl Fn(&Self, usize) -> ParseResult<T>,
+        is_truncated: impl Fn(&T) -> bool,
+    ) -> ParseResult<Vec<T>> {
+        let (count, mut index) = self.take_u32(index)?;
+        let mut items = Vec::new();
+        for _ in 0..count {
+            let (item, next_index) = take_item(self, index)?;
+            let item_truncated = is_truncated(&item);
+            items.push(item);
+            index = next_index;
+            if item_truncated {
+                break;
+            }
+        }
+        Ok((items, index))
+    }
+
+    fn take_key(&self, index: usize) -> ParseResult<Cow<'a, str>> {
+        let (len, index) = self.take_u32(index)?;
+        let len = usize::try_from(len).map_err(|_| Failure::Invalid)?;
+        if !(2..=MAX_KEY_LENGTH).contains(&len) {
+            return Err(Failure::Invalid);
+        }
+
+        let (key_bytes, index) = self.take(index, len)?;
+        let (&terminator, key_bytes) = key_bytes.split_last().ok_or(Failure::Invalid)?;
+        if terminator != 0 || key_bytes.iter().any(|&byte| byte < b' ') {
+            return Err(Failure::Invalid);
+        }
+
+        Ok((String::from_utf8_lossy(key_bytes), index))
+    }
+
+    fn take_string(&self, index: usize) -> ParseResult<Cow<'a, str>> {
+        let (len, index) = self.take_u32(index)?;
+        let len = usize::try_from(len).map_err(|_| Failure::Invalid)?;
+        let (string_bytes, index) = self.take(index, len)?;
+        Ok((decode_string(string_bytes), index))
+    }
+
+    fn take_u16(&self, index: usize) -> ParseResult<u16> {
+        let (value, index) = self.take_array(index)?;
+        Ok((u16::from_be_bytes(value), index))
+    }
+
+    fn take_u32(&self, index: usize) -> ParseResult<u32> {
+        let (value, index) = self.take_array(index)?;
+        Ok((u32::from_be_bytes(value), index))
+    }
+
+    fn take_array<const N: usize>(&self, index: usize) -> ParseResult<[u8; N]> {
+        let (bytes, index) = self.take(index, N)?;
+        let array = bytes.try_into().map_err(|_| Failure::Invalid)?;
+        Ok((array, index))
+    }
+
+    fn take(&self, index: usize, len: usize) -> ParseResult<&'a [u8]> {
+        let end = index.checked_add(len).ok_or(Failure::Invalid)?;
+        self.bytes
+            .get(index..end)
+            .map(|bytes| (bytes, end))
+            .ok_or(Failure::Truncated)
+    }
+}
+
+/// Reads a fixed number of bytes at the given index of the bytes.
+pub(crate) fn read_array<const N: usize>(bytes: &[u8], index: usize) -> Option<[u8; N]> {
+    bytes.get(index..index.checked_add(N)?)?.try_into().ok()
+}
+
+/// Reads a big-endian u32 at the given index of the bytes.
+pub(crate) fn read_u32(bytes: &[u8], index: usize) -> Option<u32> {
+    read_array(bytes, index).map(u32::from_be_bytes)
+}
+
+/// Reads a string preceded by its length at the given index of the bytes, returning the string
+/// along with the index which follows it.
+pub(crate) fn read_string(bytes: &[u8], index: usize) -> Option<(Cow<'_, str>, usize)> {
+    let len = usize::try_from(read_u32(bytes, index)?).ok()?;
+    let start = index + 4;
+    let end = start.checked_add(len)?;
+    Some((decode_string(bytes.get(start..end)?), end))
+}
+
+/// Decodes the bytes of a string, which are usually followed by a nul terminator and padding.
+fn decode_string(string_bytes: &[u8]) -> Cow<'_, str> {
+    // Older versions of before Cubase 5 didn't always provide nul terminators in strings.
+    let nul_index = string_bytes.iter().position(|&byte| byte == 0);
+    nul_index.map_or_else(
+        || String::from_utf8_lossy(string_bytes),
+        |nul_index| String::from_utf8_lossy(&string_bytes[..nul_index]),
+    )
+}
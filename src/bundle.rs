@@ -0,0 +1,404 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result, bail};
+use colored::Colorize as _;
+use flate2::read::GzDecoder;
+use zip::{DateTime, ZipArchive, extra_fields::ExtraField};
+
+/// The extensions of the zip and tar archives (referred to as bundles to distinguish them from the
+/// archives within project files) which project files are read from.
+pub const BUNDLE_EXTENSIONS: &[&str] = &["zip", "tar", "tar.gz", "tgz"];
+
+/// The separator between the path of a bundle and the path of a file within it.
+const ENTRY_SEPARATOR: &str = "!/";
+
+/// The formats of bundles which project files are read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BundleFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl BundleFormat {
+    /// Identifies the format of a bundle from its file name.
+    fn from_path(bundle_path: &Path) -> Option<Self> {
+        const FORMATS: &[(&str, BundleFormat)] = &[
+            (".zip", BundleFormat::Zip),
+            (".tar", BundleFormat::Tar),
+            (".tar.gz", BundleFormat::TarGz),
+            (".tgz", BundleFormat::TarGz),
+        ];
+
+        let file_name = bundle_path.file_name()?.to_string_lossy().to_lowercase();
+        FORMATS
+            .iter()
+            .find(|(suffix, _)| file_name.ends_with(suffix))
+            .map(|&(_, format)| format)
+    }
+}
+
+/// A project file which was read from a bundle into memory.
+pub struct BundleEntry {
+    /// Path of the bundle followed by the path of the file within it (e.g.
+    /// `Projects.zip!/Song/Song.cpr`), or the path of the bundle when the entry couldn't be
+    /// identified.
+    pub path: PathBuf,
+    /// Time the file was last modified, if it's stored in the bundle.
+    pub modified: Option<SystemTime>,
+    /// Contents of the file, or the error which prevented it from being read.
+    pub bytes: Result<Vec<u8>>,
+}
+
+impl BundleEntry {
+    fn new(
+        bundle_path: &Path,
+        entry_name: &str,
+        modified: Option<SystemTime>,
+        bytes: Result<Vec<u8>>,
+    ) -> Self {
+        Self {
+            path: PathBuf::from(format!(
+                "{}{ENTRY_SEPARATOR}{entry_name}",
+                bundle_path.display()
+            )),
+            modified,
+            bytes,
+        }
+    }
+}
+
+/// Determines whether a path refers to a zip or tar archive which project files are read from.
+pub fn is_bundle(path: &Path) -> bool {
+    BundleFormat::from_path(path).is_some()
+}
+
+/// Splits a path referring to a file within a bundle into the path of the bundle and the path of
+/// the file within it.
+pub fn split_entry_path(path: &Path) -> Option<(&Path, &str)> {
+    let path = path.to_str()?;
+    let (bundle_path, entry_name) = path.split_once(ENTRY_SEPARATOR)?;
+    let bundle_path = Path::new(bundle_path);
+    is_bundle(bundle_path).then_some((bundle_path, entry_name))
+}
+
+/// Determines whether the name of a file within a bundle has one of the given extensions.
+fn has_extension(entry_name: &str, extensions: &[&str]) -> bool {
+    Path::new(entry_name).extension().is_some_and(|extension| {
+        extensions
+            .iter()
+            .any(|candidate| extension.eq_ignore_ascii_case(candidate))
+    })
+}
+
+/// Converts the modification time of a file within a zip archive into a system time.
+///
+/// The extended timestamp field stores the time since the Unix epoch, while the time in the header
+/// of each file is stored without a time zone and is assumed to be UTC.
+fn zip_modified_time<'a>(
+    last_modified: Option<DateTime>,
+    extra_fields: impl IntoIterator<Item = &'a ExtraField>,
+) -> Option<SystemTime> {
+    let extended_time = extra_fields
+        .into_iter()
+        .find_map(|extra_field| match extra_field {
+            ExtraField::ExtendedTimestamp(timestamp) => timestamp.mod_time(),
+            ExtraField::Ntfs(_) => None,
+        });
+    if let Some(seconds) = extended_time {
+        return Some(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds.into()));
+    }
+
+    let last_modified = last_modified?;
+    humantime::parse_rfc3339(&format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        last_modified.year(),
+        last_modified.month(),
+        last_modified.day(),
+        last_modified.hour(),
+        last_modified.minute(),
+        last_modified.second()
+    ))
+    .ok()
+}
+
+/// Reads all files within a zip archive whose names are accepted by the filter, recording an error
+/// for each file which can't be read.
+fn read_zip_entries(
+    bundle_path: &Path,
+    file: BufReader<File>,
+    mut accept: impl FnMut(&str) -> bool,
+) -> Result<Vec<BundleEntry>> {
+    let mut archive = ZipArchive::new(file).context("unable to read zip archive")?;
+
+    let mut entries = Vec::new();
+    for index in 0..archive.len() {
+        let Some(entry_name) = archive.name_for_index(index).map(str::to_string) else {
+            continue;
+        };
+        if !accept(&entry_name) {
+            continue;
+        }
+
+        let mut zip_file = match archive.by_index(index) {
+            Ok(zip_file) => zip_file,
+            Err(error) => {
+                let error = anyhow::Error::from(error).context("unable to read zip archive entry");
+                entries.push(BundleEntry::new(bundle_path, &entry_name, None, Err(error)));
+                continue;
+            }
+        };
+        if zip_file.is_dir() {
+            continue;
+        }
+
+        let modified = zip_modified_time(zip_file.last_modified(), zip_file.extra_data_fields());
+        let mut bytes = Vec::new();
+        let bytes = zip_file
+            .read_to_end(&mut bytes)
+            .map(|_| bytes)
+            .with_context(|| format!("unable to read zip archive entry '{}'", entry_name.blue()));
+        entries.push(BundleEntry::new(bundle_path, &entry_name, modified, bytes));
+    }
+    Ok(entries)
+}
+
+/// Reads all files within a tar archive whose names are accepted by the filter, recording an error
+/// for each file which can't be read.
+///
+/// Reading stops at the first entry which can't be identified, since the remainder of the archive
+/// can't be located.
+fn read_tar_entries(
+    bundle_path: &Path,
+    reader: impl Read,
+    mut accept: impl FnMut(&str) -> bool,
+) -> Result<Vec<BundleEntry>> {
+    let mut archive = tar::Archive::new(reader);
+
+    let mut entries = Vec::new();
+    for tar_entry in archive.entries().context("unable to read tar archive")? {
+        let mut tar_entry = match tar_entry {
+            Ok(tar_entry) => tar_entry,
+            Err(error) => {
+                entries.push(BundleEntry {
+                    path: bundle_path.to_path_buf(),
+                    modified: None,
+                    bytes: Err(
+                        anyhow::Error::from(error).context("unable to read tar archive entry")
+                    ),
+                });
+                break;
+            }
+        };
+        if !tar_entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let entry_name = match tar_entry.path() {
+            Ok(entry_path) => entry_path.to_string_lossy().into_owned(),
+            Err(error) => {
+                entries.push(BundleEntry {
+                    path: bundle_path.to_path_buf(),
+                    modified: None,
+                    bytes: Err(
+                        anyhow::Error::from(error).context("unable to read tar archive entry path")
+                    ),
+                });
+                continue;
+            }
+        };
+        if !accept(&entry_name) {
+            continue;
+        }
+
+        let modified = tar_entry
+            .header()
+            .mtime()
+            .ok()
+            .map(|seconds| SystemTime::UNIX_EPOCH + Duration::from_secs(seconds));
+        let mut bytes = Vec::new();
+        let bytes = tar_entry
+            .read_to_end(&mut bytes)
+            .map(|_| bytes)
+            .with_context(|| format!("unable to read tar archive entry '{}'", entry_name.blue()));
+        entries.push(BundleEntry::new(bundle_path, &entry_name, modified, bytes));
+    }
+    Ok(entries)
+}
+
+/// Reads all files within a bundle whose names are accepted by the filter, in the order they
+/// are stored.
+fn read_entries(bundle_path: &Path, accept: impl FnMut(&str) -> bool) -> Result<Vec<BundleEntry>> {
+    let Some(format) = BundleFormat::from_path(bundle_path) else {
+        bail!("unsupported archive format");
+    };
+    let file = BufReader::new(File::open(bundle_path).context("unable to open archive")?);
+
+    match format {
+        BundleFormat::Zip => read_zip_entries(bundle_path, file, accept),
+        BundleFormat::Tar => read_tar_entries(bundle_path, file, accept),
+        BundleFormat::TarGz => read_tar_entries(bundle_path, GzDecoder::new(file), accept),
+    }
+}
+
+/// Reads the project files with the given extensions from a bundle into memory without
+/// extracting them to disk, recording an error for each file which can't be read.
+pub fn read_bundle_entries(bundle_path: &Path, extensions: &[&str]) -> Result<Vec<BundleEntry>> {
+    read_entries(bundle_path, |entry_name| {
+        has_extension(entry_name, extensions)
+    })
+}
+
+/// Reads a single file from a bundle into memory without extracting it to disk.
+pub fn read_bundle_entry(bundle_path: &Path, entry_name: &str) -> Result<Vec<u8>> {
+    let mut entries = read_entries(bundle_path, |name| name == entry_name)?;
+    let Some(entry) = entries.pop() else {
+        bail!("the file doesn't exist within the archive");
+    };
+    entry.bytes
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::{fs, io::Write as _};
+
+    use flate2::{Compression, write::GzEncoder};
+    use rstest::rstest;
+    use similar_asserts::assert_eq;
+    use tempfile::TempDir;
+    use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+    use super::*;
+
+    /// The files stored within each bundle along with their contents.
+    const ENTRIES: &[(&str, &[u8])] = &[
+        ("Song/Song.cpr", b"project"),
+        ("Song/Notes.txt", b"notes"),
+        ("Song/Song.bak", b"backup"),
+    ];
+
+    /// The modification time of each file within the bundles (2024-05-06T07:08:10Z).
+    const MODIFIED_SECONDS: u64 = 1_714_979_290;
+
+    fn build_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Stored)
+            .last_modified_time(DateTime::from_date_and_time(2024, 5, 6, 7, 8, 10).unwrap());
+        let mut writer = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer.add_directory("Song/", options).unwrap();
+        for (entry_name, bytes) in entries {
+            writer.start_file(*entry_name, options).unwrap();
+            writer.write_all(bytes).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn build_tar(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (entry_name, bytes) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(bytes.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(MODIFIED_SECONDS);
+            builder
+                .append_data(&mut header, entry_name, *bytes)
+                .unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn build_tar_gz(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&build_tar(entries)).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[rstest]
+    #[case::zip("Projects.zip!/Song/Song.cpr", Some(("Projects.zip", "Song/Song.cpr")))]
+    #[case::tar_gz_uppercase("Projects.TGZ!/Song.cpr", Some(("Projects.TGZ", "Song.cpr")))]
+    #[case::not_bundle("Projects!/Song.cpr", None)]
+    #[case::bundle_only("Projects.zip", None)]
+    fn test_split_entry_path(#[case] path: &str, #[case] expected: Option<(&str, &str)>) {
+        assert_eq!(
+            split_entry_path(Path::new(path)),
+            expected.map(|(bundle_path, entry_name)| (Path::new(bundle_path), entry_name))
+        );
+    }
+
+    #[rstest]
+    #[case::zip("Projects.zip", build_zip(ENTRIES))]
+    #[case::tar("Projects.tar", build_tar(ENTRIES))]
+    #[case::tar_gz("Projects.tar.gz", build_tar_gz(ENTRIES))]
+    fn test_read_bundle_entries(#[case] filename: &str, #[case] bundle_bytes: Vec<u8>) {
+        let directory = TempDir::new().unwrap();
+        let bundle_path = directory.path().join(filename);
+        fs::write(&bundle_path, bundle_bytes).unwrap();
+
+        let entries = read_bundle_entries(&bundle_path, &["cpr", "bak"]).unwrap();
+
+        let modified = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(MODIFIED_SECONDS));
+        assert_eq!(
+            entries
+                .into_iter()
+                .map(|entry| (entry.path, entry.modified, entry.bytes.unwrap()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    PathBuf::from(format!("{}!/Song/Song.cpr", bundle_path.display())),
+                    modified,
+                    b"project".to_vec(),
+                ),
+                (
+                    PathBuf::from(format!("{}!/Song/Song.bak", bundle_path.display())),
+                    modified,
+                    b"backup".to_vec(),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_bundle_entries_unreadable_entry() {
+        let directory = TempDir::new().unwrap();
+        let bundle_path = directory.path().join("Projects.zip");
+        let mut bundle_bytes = build_zip(ENTRIES);
+        let offset = bundle_bytes
+            .windows(b"project".len())
+            .position(|window| window == b"project")
+            .unwrap();
+        bundle_bytes[offset] = b'P';
+        fs::write(&bundle_path, bundle_bytes).unwrap();
+
+        let entries = read_bundle_entries(&bundle_path, &["cpr", "bak"]).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].bytes.is_err());
+        assert_eq!(entries[1].bytes.as_ref().unwrap(), b"backup");
+    }
+
+    #[rstest]
+    #[case::zip("Projects.zip", build_zip(ENTRIES))]
+    #[case::tar_gz("Projects.tgz", build_tar_gz(ENTRIES))]
+    fn test_read_bundle_entry(#[case] filename: &str, #[case] bundle_bytes: Vec<u8>) {
+        let directory = TempDir::new().unwrap();
+        let bundle_path = directory.path().join(filename);
+        fs::write(&bundle_path, bundle_bytes).unwrap();
+
+        assert_eq!(
+            read_bundle_entry(&bundle_path, "Song/Notes.txt").unwrap(),
+            b"notes"
+        );
+        assert_eq!(
+            read_bundle_entry(&bundle_path, "Song/Missing.cpr")
+                .unwrap_err()
+                .to_string(),
+            "the file doesn't exist within the archive"
+        );
+    }
+}
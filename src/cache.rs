@@ -0,0 +1,413 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write as _},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::{Context, Result};
+use colored::Colorize as _;
+use cubase_project_plugins::Project;
+use serde::{Deserialize, Serialize};
+
+/// The version of the tool, which must match for cached projects to be used.
+const TOOL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The version of the format of cached projects, which must match for cached projects to be used.
+///
+/// This must be incremented whenever the fields of `Project` or the types it contains change, as
+/// projects cached with fewer fields would otherwise be read with those fields left empty.
+const CACHE_FORMAT_VERSION: u32 = 5;
+
+/// The size and modification time of a project file, used to determine whether it has changed.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct FileStamp {
+    /// Size of the file in bytes.
+    size: u64,
+    /// Time the file was last modified.
+    modified: SystemTime,
+}
+
+impl FileStamp {
+    /// Obtains the size and modification time of a file.
+    pub fn new(path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(path).context("unable to obtain project file metadata")?;
+        Ok(Self {
+            size: metadata.len(),
+            modified: metadata
+                .modified()
+                .context("unable to obtain project file modification time")?,
+        })
+    }
+}
+
+/// A project which was parsed during a previous scan.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    /// The size and modification time of the project file when it was parsed.
+    stamp: FileStamp,
+    /// The parsed project.
+    project: Project,
+}
+
+/// The contents of the cache file.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    /// Version of the tool which wrote the cache.
+    version: String,
+    /// Version of the format of cached projects.
+    format_version: u32,
+    /// Parsed projects keyed by the absolute path of the project file.
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+/// Parsed projects from previous scans which allow unchanged project files to be skipped.
+#[derive(Debug)]
+pub struct Cache {
+    /// Path of the cache file.
+    path: PathBuf,
+    /// The contents of the cache file.
+    contents: CacheFile,
+    /// Whether any projects have been added since the cache was loaded.
+    modified: bool,
+}
+
+impl Cache {
+    /// Loads the cache file, starting with an empty cache if it doesn't exist, can't be parsed or
+    /// was written by another version of the tool or with another format of cached projects.
+    pub fn load(cache_path: &Path) -> Self {
+        let contents = File::open(cache_path)
+            .ok()
+            .and_then(|file| serde_json::from_reader::<_, CacheFile>(BufReader::new(file)).ok())
+            .filter(|contents| {
+                contents.version == TOOL_VERSION && contents.format_version == CACHE_FORMAT_VERSION
+            })
+            .unwrap_or_else(|| CacheFile {
+                version: TOOL_VERSION.to_string(),
+                format_version: CACHE_FORMAT_VERSION,
+                entries: HashMap::new(),
+            });
+
+        Self {
+            path: cache_path.to_path_buf(),
+            contents,
+            modified: false,
+        }
+    }
+
+    /// Obtains a previously parsed project if the project file hasn't changed since.
+    pub fn get(&self, project_file_path: &Path, stamp: FileStamp) -> Option<&Project> {
+        let project_file_path = std::path::absolute(project_file_path).ok()?;
+        self.contents
+            .entries
+            .get(&project_file_path)
+            .filter(|entry| entry.stamp == stamp)
+            .map(|entry| &entry.project)
+    }
+
+    /// Stores a parsed project in the cache.
+    pub fn insert(&mut self, project_file_path: &Path, stamp: FileStamp, project: Project) {
+        let Ok(project_file_path) = std::path::absolute(project_file_path) else {
+            return;
+        };
+
+        self.contents
+            .entries
+            .insert(project_file_path, CacheEntry { stamp, project });
+        self.modified = true;
+    }
+
+    /// Writes the cache file if any projects have been added or any cached project files no
+    /// longer exist, removing the projects whose files no longer exist.
+    pub fn save(&mut self) -> Result<()> {
+        let entry_count = self.contents.entries.len();
+        self.contents
+            .entries
+            .retain(|project_file_path, _| project_file_path.is_file());
+        if !self.modified && self.contents.entries.len() == entry_count {
+            return Ok(());
+        }
+
+        // The cache is written to a temporary file first so that an interrupted write doesn't
+        // leave behind a corrupt cache.
+        let temp_path = self.path.with_extension("json.tmp");
+        let write = || -> Result<()> {
+            if let Some(cache_dir) = self.path.parent() {
+                fs::create_dir_all(cache_dir)?;
+            }
+
+            let mut writer = BufWriter::new(File::create(&temp_path)?);
+            serde_json::to_writer(&mut writer, &self.contents)?;
+            writer.flush()?;
+            fs::rename(&temp_path, &self.path)?;
+            Ok(())
+        };
+        write().with_context(|| {
+            format!(
+                "unable to write cache file '{}'",
+                self.path.display().to_string().blue()
+            )
+        })?;
+
+        self.modified = false;
+        Ok(())
+    }
+
+    /// Removes the cache file.
+    pub fn clear(cache_path: &Path) -> Result<()> {
+        match fs::remove_file(cache_path) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                Err(error).with_context(|| {
+                    format!(
+                        "unable to remove cache file '{}'",
+                        cache_path.display().to_string().blue()
+                    )
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::collections::{BTreeSet, HashSet};
+
+    use cubase_project_plugins::{
+        Channel, ChannelKind, MediaFile, Metadata, Plugin, PluginCategory, PluginInstance, Tempo,
+        TimeSignature, Track, TrackKind,
+    };
+    use similar_asserts::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn project(version: &str) -> Project {
+        let plugin = Plugin {
+            guid: "D39D5B69D6AF42FA1234567868495645".to_string(),
+            name: "Hive".to_string(),
+        };
+        Project {
+            metadata: Metadata {
+                application: "Cubase".to_string(),
+                version: version.to_string(),
+                release_date: "May 22 2025".to_string(),
+                architecture: "WIN64".to_string(),
+                tempo: Some(Tempo { bpm: 120.0 }),
+                tempo_track_active: Some(false),
+                time_signature: Some(TimeSignature {
+                    numerator: 4,
+                    denominator: 4,
+                }),
+                sample_rate: Some(44100),
+                bit_depth: Some(24),
+            },
+            plugins: HashSet::from([plugin.clone()]),
+            instances: vec![PluginInstance {
+                plugin,
+                channel: Some(Channel {
+                    name: "Hive".to_string(),
+                    kind: ChannelKind::RackInstrument,
+                }),
+                category: PluginCategory::Instrument,
+                offset: 100,
+                active: true,
+                bypassed: false,
+                state: None,
+            }],
+            media: vec![MediaFile {
+                path: "Audio/Vocals.wav".to_string(),
+                relative: true,
+            }],
+            tracks: vec![Track {
+                name: "Hive".to_string(),
+                kind: TrackKind::RackInstrument,
+                depth: 0,
+            }],
+        }
+    }
+
+    /// Creates a project file along with the cache file path within a temporary directory.
+    fn create_project_file(directory: &TempDir) -> (PathBuf, PathBuf) {
+        let project_file_path = directory.path().join("Song.cpr");
+        fs::write(&project_file_path, b"RIFF").unwrap();
+        (project_file_path, directory.path().join("cache.json"))
+    }
+
+    /// Collects the path of each field within a serialized value.
+    fn collect_fields(value: &serde_json::Value, prefix: &str, fields: &mut BTreeSet<String>) {
+        match value {
+            serde_json::Value::Object(object) => {
+                for (key, value) in object {
+                    let field = format!("{prefix}.{key}");
+                    collect_fields(value, &field, fields);
+                    fields.insert(field);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    collect_fields(item, prefix, fields);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_cache_format_version() {
+        let mut fields = BTreeSet::new();
+        collect_fields(
+            &serde_json::to_value(project("14.0.30")).unwrap(),
+            "",
+            &mut fields,
+        );
+
+        // When this fails, the project model has changed and CACHE_FORMAT_VERSION must be
+        // incremented along with the expected fields below.
+        assert_eq!(
+            (CACHE_FORMAT_VERSION, fields.into_iter().collect::<Vec<_>>()),
+            (
+                5,
+                [
+                    ".instances",
+                    ".instances.active",
+                    ".instances.bypassed",
+                    ".instances.category",
+                    ".instances.channel",
+                    ".instances.channel.kind",
+                    ".instances.channel.name",
+                    ".instances.offset",
+                    ".instances.plugin",
+                    ".instances.plugin.guid",
+                    ".instances.plugin.name",
+                    ".media",
+                    ".media.path",
+                    ".media.relative",
+                    ".metadata",
+                    ".metadata.application",
+                    ".metadata.architecture",
+                    ".metadata.bit_depth",
+                    ".metadata.release_date",
+                    ".metadata.sample_rate",
+                    ".metadata.tempo",
+                    ".metadata.tempo_track_active",
+                    ".metadata.time_signature",
+                    ".metadata.time_signature.denominator",
+                    ".metadata.time_signature.numerator",
+                    ".metadata.version",
+                    ".plugins",
+                    ".plugins.guid",
+                    ".plugins.name",
+                    ".tracks",
+                    ".tracks.depth",
+                    ".tracks.kind",
+                    ".tracks.name",
+                ]
+                .map(str::to_string)
+                .to_vec()
+            )
+        );
+    }
+
+    #[test]
+    fn test_get_hit() {
+        let directory = TempDir::new().unwrap();
+        let (project_file_path, cache_path) = create_project_file(&directory);
+        let stamp = FileStamp::new(&project_file_path).unwrap();
+
+        let mut cache = Cache::load(&cache_path);
+        cache.insert(&project_file_path, stamp, project("14.0.30"));
+        cache.save().unwrap();
+        let cache = Cache::load(&cache_path);
+
+        assert_eq!(
+            cache.get(&project_file_path, stamp),
+            Some(&project("14.0.30"))
+        );
+    }
+
+    #[test]
+    fn test_get_miss() {
+        let directory = TempDir::new().unwrap();
+        let (project_file_path, cache_path) = create_project_file(&directory);
+        let stamp = FileStamp::new(&project_file_path).unwrap();
+
+        let mut cache = Cache::load(&cache_path);
+        cache.insert(&project_file_path, stamp, project("14.0.30"));
+
+        let changed_stamp = FileStamp {
+            size: stamp.size + 1,
+            ..stamp
+        };
+        assert_eq!(cache.get(&project_file_path, changed_stamp), None);
+        assert_eq!(cache.get(&directory.path().join("Other.cpr"), stamp), None);
+    }
+
+    #[rstest::rstest]
+    #[case::tool_version("0.0.0", CACHE_FORMAT_VERSION)]
+    #[case::format_version(TOOL_VERSION, CACHE_FORMAT_VERSION + 1)]
+    fn test_load_invalidated(#[case] version: &str, #[case] format_version: u32) {
+        let directory = TempDir::new().unwrap();
+        let (project_file_path, cache_path) = create_project_file(&directory);
+        let stamp = FileStamp::new(&project_file_path).unwrap();
+
+        let mut cache = Cache::load(&cache_path);
+        cache.insert(&project_file_path, stamp, project("14.0.30"));
+        cache.contents.version = version.to_string();
+        cache.contents.format_version = format_version;
+        cache.save().unwrap();
+        let cache = Cache::load(&cache_path);
+
+        assert_eq!(cache.get(&project_file_path, stamp), None);
+        assert_eq!(cache.contents.version, TOOL_VERSION);
+        assert_eq!(cache.contents.format_version, CACHE_FORMAT_VERSION);
+    }
+
+    #[test]
+    fn test_load_without_format_version() {
+        let directory = TempDir::new().unwrap();
+        let (_, cache_path) = create_project_file(&directory);
+        fs::write(
+            &cache_path,
+            format!(r#"{{"version": "{TOOL_VERSION}", "entries": {{}}}}"#),
+        )
+        .unwrap();
+
+        let cache = Cache::load(&cache_path);
+
+        assert_eq!(cache.contents.format_version, CACHE_FORMAT_VERSION);
+    }
+
+    #[test]
+    fn test_save_prunes_removed_project_files() {
+        let directory = TempDir::new().unwrap();
+        let (project_file_path, cache_path) = create_project_file(&directory);
+        let stamp = FileStamp::new(&project_file_path).unwrap();
+
+        let mut cache = Cache::load(&cache_path);
+        cache.insert(&project_file_path, stamp, project("14.0.30"));
+        cache.save().unwrap();
+
+        fs::remove_file(&project_file_path).unwrap();
+        let mut cache = Cache::load(&cache_path);
+        assert_eq!(cache.contents.entries.len(), 1);
+        cache.save().unwrap();
+        let cache = Cache::load(&cache_path);
+
+        assert!(cache.contents.entries.is_empty());
+    }
+
+    #[test]
+    fn test_clear() {
+        let directory = TempDir::new().unwrap();
+        let (_, cache_path) = create_project_file(&directory);
+        fs::write(&cache_path, "{}").unwrap();
+
+        Cache::clear(&cache_path).unwrap();
+        Cache::clear(&cache_path).unwrap();
+
+        assert!(!cache_path.exists());
+    }
+}
@@ -0,0 +1,236 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{Context, Error, Result};
+use colored::Colorize as _;
+use cubase_project_plugins::{PluginFormat, steinberg};
+use serde::{Deserialize, Serialize};
+
+/// The vendor of the plugins which are bundled with Cubase.
+const STEINBERG_VENDOR: &str = "Steinberg";
+
+/// Describes a plugin listed in a catalog.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CatalogEntry {
+    /// Globally unique identifier for the plugin.
+    #[serde(skip_serializing)]
+    pub guid: String,
+    /// Company which makes the plugin.
+    pub vendor: String,
+    /// Name of the product which includes the plugin.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product: Option<String>,
+    /// Type of plugin, such as a synthesizer or reverb.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Licence under which the plugin is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub licence: Option<String>,
+    /// Whether the plugin is bundled with Cubase rather than being a third-party plugin.
+    #[serde(default)]
+    pub stock: bool,
+}
+
+/// A catalog file listing plugins along with their vendors.
+#[derive(Debug, Deserialize)]
+struct CatalogFile {
+    /// Plugins in the catalog.
+    plugins: Vec<CatalogEntry>,
+}
+
+/// Plugins along with their vendors, used to group plugins by vendor and to identify the plugins
+/// which are bundled with Cubase.
+#[derive(Debug)]
+pub struct Catalog {
+    /// Catalog entries keyed by GUID in uppercase.
+    entries: HashMap<String, CatalogEntry>,
+}
+
+impl Default for Catalog {
+    /// Creates a catalog of the plugins which are bundled with Cubase.
+    fn default() -> Self {
+        let mut catalog = Self {
+            entries: HashMap::new(),
+        };
+        for &(guid, name) in steinberg::STOCK_PLUGINS {
+            catalog.add(CatalogEntry {
+                guid: guid.to_string(),
+                vendor: STEINBERG_VENDOR.to_string(),
+                product: Some(name.to_string()),
+                category: None,
+                licence: None,
+                stock: true,
+            });
+        }
+        catalog
+    }
+}
+
+impl Catalog {
+    /// Loads a catalog from a TOML or JSON file listing plugins, adding its plugins to the
+    /// plugins which are bundled with Cubase and replacing any with the same GUID.
+    pub fn load(catalog_path: &Path) -> Result<Self> {
+        let catalog_string = fs::read_to_string(catalog_path).with_context(|| {
+            format!(
+                "unable to open and read catalog file '{}'",
+                catalog_path.display().to_string().blue()
+            )
+        })?;
+
+        let is_json = catalog_path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        let catalog_file: CatalogFile = if is_json {
+            serde_json::from_str(&catalog_string).map_err(Error::from)
+        } else {
+            toml::from_str(&catalog_string).map_err(Error::from)
+        }
+        .with_context(|| {
+            format!(
+                "unable to parse catalog file '{}'",
+                catalog_path.display().to_string().blue()
+            )
+        })?;
+
+        let mut catalog = Self::default();
+        for entry in catalog_file.plugins {
+            catalog.add(entry);
+        }
+        Ok(catalog)
+    }
+
+    fn add(&mut self, entry: CatalogEntry) {
+        self.entries.insert(entry.guid.to_uppercase(), entry);
+    }
+
+    /// Looks up the plugin with the given GUID.
+    pub fn get(&self, guid: &str) -> Option<&CatalogEntry> {
+        self.entries.get(&guid.to_uppercase())
+    }
+
+    /// Determines the vendor of the plugin with the given GUID, if it's listed.
+    pub fn vendor(&self, guid: &str) -> Option<&str> {
+        self.get(guid).map(|entry| entry.vendor.as_str())
+    }
+
+    /// Determines whether the plugin with the given GUID is bundled with Cubase.
+    pub fn is_stock(&self, guid: &str) -> bool {
+        self.get(guid).is_some_and(|entry| entry.stock)
+    }
+
+    /// Determines the format of the plugin with the given GUID, treating the plugins which are
+    /// bundled with Cubase as built-in.
+    pub fn format(&self, guid: &str) -> PluginFormat {
+        PluginFormat::from_guid(guid, self.is_stock(guid))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use rstest::rstest;
+    use similar_asserts::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const CATALOG_TOML: &str = r#"
+        [[plugins]]
+        guid = "56535473796c3173796c656e74683100"
+        vendor = "LennarDigital"
+        product = "Sylenth1"
+        category = "Synth"
+        licence = "Commercial"
+
+        [[plugins]]
+        guid = "946051208E29496E804F64A825C8A047"
+        vendor = "Example Vendor"
+        "#;
+
+    const CATALOG_JSON: &str = r#"{
+        "plugins": [
+            {
+                "guid": "56535473796c3173796c656e74683100",
+                "vendor": "LennarDigital",
+                "product": "Sylenth1",
+                "category": "Synth",
+                "licence": "Commercial"
+            },
+            {
+                "guid": "946051208E29496E804F64A825C8A047",
+                "vendor": "Example Vendor"
+            }
+        ]
+    }"#;
+
+    #[rstest]
+    #[case::toml("catalog.toml", CATALOG_TOML)]
+    #[case::json("catalog.json", CATALOG_JSON)]
+    fn test_load(#[case] filename: &str, #[case] contents: &str) {
+        let directory = TempDir::new().unwrap();
+        let catalog_path = directory.path().join(filename);
+        fs::write(&catalog_path, contents).unwrap();
+
+        let catalog = Catalog::load(&catalog_path).unwrap();
+
+        let entry = catalog.get("56535473796C3173796C656E74683100").unwrap();
+        assert_eq!(entry.vendor, "LennarDigital");
+        assert_eq!(entry.product.as_deref(), Some("Sylenth1"));
+        assert_eq!(entry.category.as_deref(), Some("Synth"));
+        assert_eq!(entry.licence.as_deref(), Some("Commercial"));
+        assert!(!entry.stock);
+
+        // Plugins bundled with Cubase remain in the catalog unless they're replaced.
+        assert_eq!(
+            catalog.vendor("44E1149EDB3E4387BDD827FEA3A39EE7"),
+            Some(STEINBERG_VENDOR)
+        );
+    }
+
+    #[test]
+    fn test_load_invalid() {
+        let directory = TempDir::new().unwrap();
+        let catalog_path = directory.path().join("catalog.json");
+        fs::write(&catalog_path, CATALOG_TOML).unwrap();
+
+        assert!(Catalog::load(&catalog_path).is_err());
+    }
+
+    #[test]
+    fn test_load_replaces_stock_plugins() {
+        let directory = TempDir::new().unwrap();
+        let catalog_path = directory.path().join("catalog.toml");
+        fs::write(&catalog_path, CATALOG_TOML).unwrap();
+
+        let catalog = Catalog::load(&catalog_path).unwrap();
+
+        assert_eq!(
+            catalog.vendor("946051208E29496E804F64A825C8A047"),
+            Some("Example Vendor")
+        );
+        assert!(!catalog.is_stock("946051208E29496E804F64A825C8A047"));
+        assert_eq!(
+            catalog.format("946051208E29496E804F64A825C8A047"),
+            PluginFormat::Vst3
+        );
+    }
+
+    #[rstest]
+    #[case::uppercase("946051208E29496E804F64A825C8A047")]
+    #[case::lowercase("946051208e29496e804f64a825c8a047")]
+    fn test_get_ignores_case(#[case] guid: &str) {
+        let catalog = Catalog::default();
+
+        assert_eq!(catalog.vendor(guid), Some(STEINBERG_VENDOR));
+        assert!(catalog.is_stock(guid));
+    }
+
+    #[rstest]
+    #[case::stock("946051208E29496E804F64A825C8A047", PluginFormat::BuiltIn)]
+    #[case::stock_vst2("56535455564852757632326872000000", PluginFormat::BuiltIn)]
+    #[case::third_party("D39D5B69D6AF42FA1234567868495645", PluginFormat::Vst3)]
+    fn test_format(#[case] guid: &str, #[case] expected: PluginFormat) {
+        let catalog = Catalog::default();
+
+        assert_eq!(catalog.format(guid), expected);
+    }
+}
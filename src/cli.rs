@@ -1,23 +1,143 @@
-use std::path::PathBuf;
+use std::{ffi::OsString, num::NonZeroUsize, path::PathBuf};
 
-use clap::{Parser, ValueHint};
+use clap::{Args, CommandFactory as _, Parser, Subcommand, ValueEnum, ValueHint, error::ErrorKind};
 use clap_complete::Shell;
+use cubase_project_plugins::PluginCategory;
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct Cli {
-    /// Directory paths to search for Cubase projects.
+    /// The command to run, which defaults to scanning projects.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Options used to scan projects when no command is given.
+    #[command(flatten)]
+    pub scan: ScanArgs,
+}
+
+impl Cli {
+    /// Parses the command line arguments into the command to run, exiting with an error when they
+    /// are invalid.
+    #[must_use]
+    pub fn parse_command() -> Command {
+        Self::try_parse_command_from(std::env::args_os()).unwrap_or_else(|error| error.exit())
+    }
+
+    /// Parses the given arguments into the command to run, scanning projects when no command is
+    /// given.
+    ///
+    /// Options given before the name of a command prevent the command from being recognised, so
+    /// its name would otherwise be scanned as a project path. These arguments are rejected instead.
+    pub fn try_parse_command_from(
+        args: impl IntoIterator<Item = impl Into<OsString> + Clone>,
+    ) -> Result<Command, clap::Error> {
+        let cli = Self::try_parse_from(args)?;
+        if cli.command.is_none()
+            && let Some(name) = cli.scan.project_paths.first()
+            && Self::command().find_subcommand(name).is_some()
+        {
+            return Err(Self::command().error(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "options must be given after the '{name}' command (use './{name}' to scan a \
+                     directory with this name)"
+                ),
+            ));
+        }
+        Ok(cli.command.unwrap_or(Command::Scan(cli.scan)))
+    }
+}
+
+/// The operations which the tool can perform.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Show the plugins used by each project followed by summaries across all projects.
+    Scan(ScanArgs),
+    /// Only show the summaries of plugins and Cubase versions used across all projects.
+    Summary(SummaryArgs),
+    /// List the projects which use a plugin matching a GUID or name wildcard pattern as a table.
+    WhoUses(WhoUsesArgs),
+    /// Show everything about a single project, including its media files and tracks.
+    Inspect(InspectArgs),
+    /// Compare the Cubase version, architecture and plugins of two projects.
+    Diff(DiffArgs),
+    /// Show how the plugins and Cubase version of each project evolved across its saved versions.
+    History(HistoryArgs),
+    /// Remove cached projects from previous scans.
+    ClearCache,
+    /// Generate shell completions.
+    Completions(CompletionsArgs),
+}
+
+/// Options which are shared by the commands that read projects.
+#[derive(Args, Debug)]
+pub struct ProjectOptions {
+    /// Config file path.
+    #[arg(short, long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub config_path: Option<PathBuf>,
+
+    /// Inventory of installed plugins used to find plugins which projects are missing, either as
+    /// a TOML or JSON file or a directory of VST3 plugins.
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::AnyPath)]
+    pub inventory: Option<PathBuf>,
+
+    /// Catalog of plugins along with their vendors as a TOML or JSON file, which overrides the
+    /// catalog path in the config file.
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub catalog: Option<PathBuf>,
+
+    /// Exclude plugins which are bundled with Cubase.
+    #[arg(long)]
+    pub exclude_stock: bool,
+
+    /// How plugins which are inserted but bypassed or deactivated are treated.
+    ///
+    /// Only deactivated plugins and those within a bypassed insert or channel strip section are
+    /// detected, since bypassing a single insert isn't recorded in a way which is understood.
+    #[arg(long, value_enum, value_name = "MODE", default_value_t = Inactive::Include)]
+    pub inactive: Inactive,
+
+    /// Only include plugins used in the given categories (e.g. instruments or inserts).
+    #[arg(name = "category", long, value_enum, value_name = "CATEGORY")]
+    pub categories: Vec<Category>,
+
+    /// Number of project files to read and parse concurrently [default: number of CPUs].
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// Read every project file rather than using cached projects from previous scans.
+    #[arg(long)]
+    pub no_cache: bool,
+}
+
+/// Options for showing the plugins used by each project.
+#[derive(Args, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ScanArgs {
+    /// Directories, project files, zip or tar archives or glob patterns to search for Cubase
+    /// projects, or - to read a project or a list of project paths from standard input.
     #[arg(
         value_name = "PROJECT_PATH",
-        value_hint = ValueHint::DirPath,
-        required_unless_present = "completions",
-        conflicts_with = "completions"
+        value_hint = ValueHint::AnyPath,
+        required_unless_present_any = ["completions", "clear_cache"],
+        conflicts_with_all = ["completions", "clear_cache"]
     )]
     pub project_paths: Vec<String>,
 
-    /// Config file path.
-    #[arg(short, long, value_name = "PATH", value_hint = ValueHint::FilePath)]
-    pub config_path: Option<PathBuf>,
+    #[command(flatten)]
+    pub options: ProjectOptions,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
 
     /// Filter projects based on a plugin name or GUID using a wildcard pattern.
     #[arg(name = "filter", short, long, value_name = "PATTERN")]
@@ -27,12 +147,398 @@ pub struct Cli {
     #[arg(short, long)]
     pub only_show_filtered: bool,
 
+    /// List the projects which use a plugin matching a GUID or name wildcard pattern as a table
+    /// rather than showing each project.
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        conflicts_with_all = ["filter", "only_show_filtered", "watch", "summary_csv"]
+    )]
+    pub who_uses: Vec<String>,
+
+    /// Order of the projects listed by --who-uses.
+    #[arg(long, value_enum, value_name = "ORDER", default_value_t = SortOrder::Path)]
+    pub sort: SortOrder,
+
+    /// List the media files referenced by the pool of each project, flagging those which don't
+    /// exist.
+    #[arg(short, long)]
+    pub media: bool,
+
+    /// List the tracks of each project along with their types and folders.
+    #[arg(short, long)]
+    pub tracks: bool,
+
+    /// Also scan the backup (*.bak) and auto-save (*.csh) files which Cubase writes, labelling
+    /// them in the output and excluding them from the summaries.
+    #[arg(short, long)]
+    pub backups: bool,
+
+    /// Count backup and auto-save files towards the summaries.
+    #[arg(long, requires = "backups")]
+    pub count_backups: bool,
+
+    #[command(flatten)]
+    pub states: StateOptions,
+
+    /// Write summary counts of plugins and Cubase versions to a CSV file.
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub summary_csv: Option<PathBuf>,
+
+    /// Keep running after the initial scan and rescan projects which are added, modified or
+    /// removed within the given directories, along with the given project files.
+    #[arg(short, long)]
+    pub watch: bool,
+
+    /// Remove cached projects from previous scans.
+    #[arg(long)]
+    pub clear_cache: bool,
+
     /// Generate shell completions.
     #[arg(long, value_name = "SHELL")]
     pub completions: Option<Shell>,
 }
 
+/// Options for reading and exporting the saved state of each plugin instance.
+#[derive(Args, Debug)]
+pub struct StateOptions {
+    /// Read the saved state of each plugin instance, showing the name of any preset it has loaded
+    /// and including the state in JSON output.
+    #[arg(long)]
+    pub states: bool,
+
+    /// Encoding of plugin states within JSON output.
+    #[arg(long, value_enum, value_name = "ENCODING", default_value_t = StateEncoding::Base64)]
+    pub state_encoding: StateEncoding,
+
+    /// Directory to write the state of each plugin instance to as .vstpreset files, within a
+    /// subdirectory named after each project file.
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::DirPath)]
+    pub export_presets: Option<PathBuf>,
+}
+
+/// Options for showing the summaries of plugins and Cubase versions used across all projects.
+#[derive(Args, Debug)]
+pub struct SummaryArgs {
+    /// Directories, project files, zip or tar archives or glob patterns to search for Cubase
+    /// projects, or - to read a project or a list of project paths from standard input.
+    #[arg(value_name = "PROJECT_PATH", value_hint = ValueHint::AnyPath, required = true)]
+    pub project_paths: Vec<String>,
+
+    #[command(flatten)]
+    pub options: ProjectOptions,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    /// Only include plugins matching a plugin name or GUID wildcard pattern in the summaries.
+    #[arg(name = "filter", short, long, value_name = "PATTERN")]
+    pub patterns: Vec<String>,
+
+    /// Write summary counts of plugins and Cubase versions to a CSV file.
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub summary_csv: Option<PathBuf>,
+}
+
+/// Options for listing the projects which use a plugin.
+#[derive(Args, Debug)]
+pub struct WhoUsesArgs {
+    /// GUID or name wildcard pattern of the plugin to look up.
+    #[arg(value_name = "PATTERN")]
+    pub pattern: String,
+
+    /// Directories, project files, zip or tar archives or glob patterns to search for Cubase
+    /// projects, or - to read a project or a list of project paths from standard input.
+    #[arg(value_name = "PROJECT_PATH", value_hint = ValueHint::AnyPath, required = true)]
+    pub project_paths: Vec<String>,
+
+    #[command(flatten)]
+    pub options: ProjectOptions,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    /// Order of the projects listed.
+    #[arg(long, value_enum, value_name = "ORDER", default_value_t = SortOrder::Path)]
+    pub sort: SortOrder,
+}
+
+/// Options for showing everything about a single project.
+#[derive(Args, Debug)]
+pub struct InspectArgs {
+    /// Path of the Cubase project file.
+    #[arg(value_name = "PROJECT_FILE", value_hint = ValueHint::FilePath)]
+    pub project_file_path: PathBuf,
+
+    #[command(flatten)]
+    pub options: ProjectOptions,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    #[command(flatten)]
+    pub states: StateOptions,
+}
+
+/// Options for comparing two projects.
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    /// Path of the old Cubase project file.
+    #[arg(value_name = "OLD_PROJECT_FILE", value_hint = ValueHint::FilePath)]
+    pub old_project_file_path: PathBuf,
+
+    /// Path of the new Cubase project file.
+    #[arg(value_name = "NEW_PROJECT_FILE", value_hint = ValueHint::FilePath)]
+    pub new_project_file_path: PathBuf,
+
+    #[command(flatten)]
+    pub options: ProjectOptions,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = DiffFormat::Text)]
+    pub format: DiffFormat,
+}
+
+/// Options for showing how projects evolved across their saved versions.
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    /// Directories, project files, zip or tar archives or glob patterns to search for Cubase
+    /// projects, or - to read a project or a list of project paths from standard input.
+    #[arg(value_name = "PROJECT_PATH", value_hint = ValueHint::AnyPath, required = true)]
+    pub project_paths: Vec<String>,
+
+    #[command(flatten)]
+    pub options: ProjectOptions,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = DiffFormat::Text)]
+    pub format: DiffFormat,
+
+    /// Order of the versions of each project.
+    #[arg(long, value_enum, value_name = "ORDER", default_value_t = VersionOrder::Version)]
+    pub sort: VersionOrder,
+}
+
+/// Options for generating shell completions.
+#[derive(Args, Debug)]
+pub struct CompletionsArgs {
+    /// Shell to generate completions for.
+    #[arg(value_name = "SHELL")]
+    pub shell: Shell,
+}
+
+/// The format used to display the results of a scan.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Coloured human-readable text.
+    Text,
+    /// A single JSON document containing all projects, errors and summaries.
+    Json,
+    /// One CSV row per plugin used in each project.
+    Csv,
+}
+
+/// The format used to display the changes between projects, which can't be represented as rows of
+/// plugins.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffFormat {
+    /// Coloured human-readable text.
+    Text,
+    /// A single JSON document containing all changes.
+    Json,
+}
+
+impl From<DiffFormat> for Format {
+    fn from(format: DiffFormat) -> Self {
+        match format {
+            DiffFormat::Text => Self::Text,
+            DiffFormat::Json => Self::Json,
+        }
+    }
+}
+
+/// The order in which projects are listed.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Sorted by the path of the project file.
+    Path,
+    /// Sorted by the time the project file was last modified, oldest first.
+    Date,
+}
+
+/// The order in which the versions of a project are listed.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionOrder {
+    /// Sorted by the version suffix of the project file (e.g. Project-01.cpr), with each backup
+    /// preceding the project file saved over it.
+    Version,
+    /// Sorted by the time the project file was last modified, oldest first.
+    Date,
+}
+
+/// The encoding used for plugin states within JSON output.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateEncoding {
+    /// Hexadecimal digits.
+    Hex,
+    /// Standard base64 with padding.
+    Base64,
+}
+
+/// How plugins which are inserted but bypassed or deactivated are treated.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inactive {
+    /// Treat them like any other plugin.
+    Include,
+    /// Leave them out of the output and summaries.
+    Exclude,
+    /// Include them but highlight the number of bypassed or deactivated instances.
+    Highlight,
+}
+
+/// The role which a plugin plays on the track or channel it belongs to.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    /// Instruments on instrument tracks or in the VST instrument rack.
+    Instrument,
+    /// Insert effects on tracks and channels other than FX channels.
+    Insert,
+    /// Insert effects on FX channels which are fed by sends.
+    SendEffect,
+    /// Channel strip modules such as the EQ or input filter.
+    ChannelStrip,
+    /// Panners of channels and sends.
+    Panner,
+    /// Inserts which only process MIDI.
+    MidiInsert,
+    /// Plugins whose role couldn't be determined.
+    Unknown,
+}
+
+impl From<Category> for PluginCategory {
+    fn from(category: Category) -> Self {
+        match category {
+            Category::Instrument => Self::Instrument,
+            Category::Insert => Self::Insert,
+            Category::SendEffect => Self::SendEffect,
+            Category::ChannelStrip => Self::ChannelStrip,
+            Category::Panner => Self::Panner,
+            Category::MidiInsert => Self::MidiInsert,
+            Category::Unknown => Self::Unknown,
+        }
+    }
+}
+
 #[must_use]
 pub fn default_config_path() -> Option<PathBuf> {
     dirs::home_dir().map(|home_dir| home_dir.join(".config").join("cubase-project-plugins.toml"))
 }
+
+#[must_use]
+pub fn default_cache_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|cache_dir| cache_dir.join("cubase-project-plugins.json"))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::iter;
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn test_command_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[rstest]
+    #[case::scan(&["--filter", "Sylenth1", "Projects"], "Projects")]
+    #[case::scan_command(&["scan", "--filter", "Sylenth1", "Projects"], "Projects")]
+    #[case::directory_named_after_command(&["--filter", "Sylenth1", "./history"], "./history")]
+    fn test_parse_scan(#[case] args: &[&str], #[case] expected_project_path: &str) {
+        let command = Cli::try_parse_command_from(
+            iter::once("cubase-project-plugins").chain(args.iter().copied()),
+        )
+        .unwrap();
+
+        let Command::Scan(scan_args) = command else {
+            unreachable!("expected the scan command but found {command:?}");
+        };
+        assert_eq!(scan_args.project_paths, [expected_project_path]);
+        assert_eq!(scan_args.patterns, ["Sylenth1"]);
+    }
+
+    #[test]
+    fn test_parse_command_with_options() {
+        let command = Cli::try_parse_command_from([
+            "cubase-project-plugins",
+            "who-uses",
+            "-c",
+            "config.toml",
+            "StudioEQ",
+            "Projects",
+        ])
+        .unwrap();
+
+        let Command::WhoUses(who_uses_args) = command else {
+            unreachable!("expected the who-uses command but found {command:?}");
+        };
+        assert_eq!(who_uses_args.pattern, "StudioEQ");
+        assert_eq!(who_uses_args.project_paths, ["Projects"]);
+        assert_eq!(
+            who_uses_args.options.config_path,
+            Some(PathBuf::from("config.toml"))
+        );
+    }
+
+    #[rstest]
+    #[case::who_uses(&["-c", "config.toml", "who-uses", "StudioEQ", "Projects"])]
+    #[case::history(&["--no-cache", "history", "Projects"])]
+    fn test_parse_options_before_command(#[case] args: &[&str]) {
+        let error = Cli::try_parse_command_from(
+            iter::once("cubase-project-plugins").chain(args.iter().copied()),
+        )
+        .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn test_parse_clear_cache() {
+        let command =
+            Cli::try_parse_command_from(["cubase-project-plugins", "clear-cache"]).unwrap();
+
+        assert!(matches!(command, Command::ClearCache));
+    }
+
+    #[rstest]
+    #[case::diff(&["diff", "--format", "csv", "Old.cpr", "New.cpr"])]
+    #[case::history(&["history", "--format", "csv", "Projects"])]
+    fn test_parse_csv_format_unsupported(#[case] args: &[&str]) {
+        let error = Cli::try_parse_command_from(
+            iter::once("cubase-project-plugins").chain(args.iter().copied()),
+        )
+        .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn test_category_conversion() {
+        for category in Category::value_variants() {
+            let name = category
+                .to_possible_value()
+                .unwrap()
+                .get_name()
+                .replace('-', "_");
+            let plugin_category = PluginCategory::from(*category);
+
+            assert_eq!(serde_json::to_value(plugin_category).unwrap(), name);
+        }
+    }
+}
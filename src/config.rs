@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use serde::Deserialize;
 
 /// Project specific configuration for the tool.
@@ -16,6 +18,9 @@ pub struct Plugins {
     pub guid_ignores: Vec<String>,
     /// Plugin names which should be ignored.
     pub name_ignores: Vec<String>,
+    /// Whether plugins which are bundled with Cubase should be ignored.
+    #[serde(default)]
+    pub ignore_stock: bool,
 }
 
 /// The main configuration structure for the tool.
@@ -23,6 +28,9 @@ pub struct Plugins {
 pub struct Config {
     /// Project path patterns to skip.
     pub path_ignore_patterns: Vec<String>,
+    /// Path of a catalog file listing plugins along with their vendors.
+    #[serde(default)]
+    pub catalog_path: Option<PathBuf>,
     /// Configuration related to projects.
     pub projects: Projects,
     /// Configuration related to plugins.
@@ -33,6 +41,7 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             path_ignore_patterns: Vec::new(),
+            catalog_path: None,
             projects: Projects {
                 report_32_bit: true,
                 report_64_bit: true,
@@ -40,6 +49,7 @@ fn default() -> Self {
             plugins: Plugins {
                 guid_ignores: Vec::new(),
                 name_ignores: Vec::new(),
+                ignore_stock: false,
             },
         }
     }
@@ -0,0 +1,345 @@
+//! Compares two Cubase projects, such as two revisions of the same project, to determine which
+//! plugins were added, removed or renamed between them.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::project::{Channel, Plugin, PluginCategory, Project};
+
+/// A value which differs between two projects.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Change<T> {
+    /// The value in the old project.
+    pub old: T,
+    /// The value in the new project.
+    pub new: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    /// Creates a change when the old and new values differ.
+    fn between(old: T, new: T) -> Option<Self> {
+        (old != new).then_some(Self { old, new })
+    }
+}
+
+/// The insert effects which were added to or removed from a single track or channel.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ChannelDiff {
+    /// The track or channel.
+    pub channel: Channel,
+    /// Plugins inserted in the new project which weren't in the old project.
+    pub added: Vec<Plugin>,
+    /// Plugins inserted in the old project which aren't in the new project.
+    pub removed: Vec<Plugin>,
+}
+
+/// The differences between two Cubase projects.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ProjectDiff {
+    /// The change in the version of Cubase used to create the project, if any.
+    pub version: Option<Change<String>>,
+    /// The change in the architecture of Cubase used to create the project, if any.
+    pub architecture: Option<Change<String>>,
+    /// Plugins which are only used in the new project, sorted by name.
+    pub added_plugins: Vec<Plugin>,
+    /// Plugins which are only used in the old project, sorted by name.
+    pub removed_plugins: Vec<Plugin>,
+    /// Plugins which kept their GUID but changed their name, or kept their name but changed their
+    /// GUID, sorted by their old name.
+    pub renamed_plugins: Vec<Change<Plugin>>,
+    /// Tracks and channels whose insert effects changed, in the order they appear in the new
+    /// project followed by those which only appear in the old project.
+    pub channels: Vec<ChannelDiff>,
+}
+
+impl ProjectDiff {
+    /// Compares an old project with a new project.
+    #[must_use]
+    pub fn new(old: &Project, new: &Project) -> Self {
+        let (added_plugins, removed_plugins, renamed_plugins) =
+            diff_plugins(&old.plugins, &new.plugins);
+
+        Self {
+            version: Change::between(old.metadata.version.clone(), new.metadata.version.clone()),
+            architecture: Change::between(
+                old.metadata.architecture.clone(),
+                new.metadata.architecture.clone(),
+            ),
+            added_plugins,
+            removed_plugins,
+            renamed_plugins,
+            channels: diff_channels(old, new),
+        }
+    }
+
+    /// Determines whether the projects have no differences.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.version.is_none()
+            && self.architecture.is_none()
+            && self.added_plugins.is_empty()
+            && self.removed_plugins.is_empty()
+            && self.renamed_plugins.is_empty()
+            && self.channels.is_empty()
+    }
+}
+
+/// Sorts plugins by name followed by GUID.
+fn sort_plugins(plugins: &mut [Plugin]) {
+    plugins.sort_by_cached_key(|plugin| (plugin.name.to_lowercase(), plugin.guid.clone()));
+}
+
+fn have_same_guid(old: &Plugin, new: &Plugin) -> bool {
+    old.guid == new.guid
+}
+
+fn have_same_name(old: &Plugin, new: &Plugin) -> bool {
+    old.name.eq_ignore_ascii_case(&new.name)
+}
+
+/// Determines the plugins which were added, removed and renamed, pairing plugins with the same
+/// GUID before those with the same name.
+fn diff_plugins(
+    old: &HashSet<Plugin>,
+    new: &HashSet<Plugin>,
+) -> (Vec<Plugin>, Vec<Plugin>, Vec<Change<Plugin>>) {
+    let mut added = new.difference(old).cloned().collect::<Vec<_>>();
+    let mut removed = old.difference(new).cloned().collect::<Vec<_>>();
+    sort_plugins(&mut added);
+    sort_plugins(&mut removed);
+
+    let mut renamed = Vec::new();
+    for is_same_plugin in [have_same_guid, have_same_name] {
+        removed.retain(|old_plugin| {
+            let Some(index) = added
+                .iter()
+                .position(|new_plugin| is_same_plugin(old_plugin, new_plugin))
+            else {
+                return true;
+            };
+            renamed.push(Change {
+                old: old_plugin.clone(),
+                new: added.remove(index),
+            });
+            false
+        });
+    }
+    renamed.sort_by_cached_key(|change| (change.old.name.to_lowercase(), change.old.guid.clone()));
+
+    (added, removed, renamed)
+}
+
+/// Groups the insert effects of a project by their track or channel, in the order the tracks and
+/// channels first appear.
+fn channel_inserts(project: &Project) -> Vec<(&Channel, Vec<&Plugin>)> {
+    let mut channel_inserts = Vec::<(&Channel, Vec<&Plugin>)>::new();
+    for instance in &project.instances {
+        let Some(channel) = &instance.channel else {
+            continue;
+        };
+        if !matches!(
+            instance.category,
+            PluginCategory::Insert | PluginCategory::SendEffect | PluginCategory::MidiInsert
+        ) {
+            continue;
+        }
+
+        match channel_inserts
+            .iter_mut()
+            .find(|(inserts_channel, _)| *inserts_channel == channel)
+        {
+            Some((_, plugins)) => plugins.push(&instance.plugin),
+            None => channel_inserts.push((channel, vec![&instance.plugin])),
+        }
+    }
+    channel_inserts
+}
+
+/// Looks up the insert effects of a track or channel.
+fn plugins_on<'a>(inserts: &'a [(&Channel, Vec<&Plugin>)], channel: &Channel) -> &'a [&'a Plugin] {
+    inserts
+        .iter()
+        .find(|(inserts_channel, _)| *inserts_channel == channel)
+        .map_or(&[], |(_, plugins)| plugins.as_slice())
+}
+
+/// Determines the plugins in the first list which aren't matched by a plugin with the same GUID
+/// in the second list, with each plugin in the second list matching at most one plugin.
+fn unmatched_plugins(plugins: &[&Plugin], other_plugins: &[&Plugin]) -> Vec<Plugin> {
+    let mut other_plugins = other_plugins.to_vec();
+    plugins
+        .iter()
+        .filter(|plugin| {
+            let Some(index) = other_plugins
+                .iter()
+                .position(|other_plugin| have_same_guid(plugin, other_plugin))
+            else {
+                return true;
+            };
+            other_plugins.remove(index);
+            false
+        })
+        .map(|&plugin| plugin.clone())
+        .collect()
+}
+
+/// Determines the insert effects which were added to or removed from each track and channel.
+fn diff_channels(old: &Project, new: &Project) -> Vec<ChannelDiff> {
+    let old_inserts = channel_inserts(old);
+    let new_inserts = channel_inserts(new);
+
+    let mut channels = new_inserts
+        .iter()
+        .map(|(channel, _)| *channel)
+        .collect::<Vec<_>>();
+    for (channel, _) in &old_inserts {
+        if !channels.contains(channel) {
+            channels.push(channel);
+        }
+    }
+
+    channels
+        .into_iter()
+        .filter_map(|channel| {
+            let old_plugins = plugins_on(&old_inserts, channel);
+            let new_plugins = plugins_on(&new_inserts, channel);
+            let added = unmatched_plugins(new_plugins, old_plugins);
+            let removed = unmatched_plugins(old_plugins, new_plugins);
+            (!added.is_empty() || !removed.is_empty()).then(|| ChannelDiff {
+                channel: channel.clone(),
+                added,
+                removed,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use rstest::rstest;
+    use similar_asserts::assert_eq;
+
+    use super::*;
+    use crate::{project::ChannelKind, reader::Reader};
+
+    fn read_project(filename: &str) -> Project {
+        let project_path = PathBuf::from("testdata").join(filename);
+        let project_bytes = fs::read(project_path).unwrap();
+        Reader::new(&project_bytes).get_project_details().unwrap()
+    }
+
+    fn plugin(guid: &str, name: &str) -> Plugin {
+        Plugin {
+            guid: guid.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    #[rstest]
+    #[case::cubase_4_5_32_bit("Example Project (Cubase 4.5 32-bit).cpr")]
+    #[case::cubase_14("Example Project (Cubase 14).cpr")]
+    fn test_project_diff_identical(#[case] filename: &str) {
+        let project = read_project(filename);
+
+        let diff = ProjectDiff::new(&project, &project);
+
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_project_diff_revisions() {
+        let old = read_project("Example Project (Cubase 11).cpr");
+        let new = read_project("Example Project (Cubase 14).cpr");
+
+        let diff = ProjectDiff::new(&old, &new);
+
+        assert_eq!(
+            diff.version,
+            Some(Change {
+                old: "11.0.41".to_string(),
+                new: "14.0.30".to_string(),
+            })
+        );
+        assert_eq!(diff.architecture, None);
+        assert_eq!(diff.added_plugins, Vec::new());
+        assert_eq!(diff.removed_plugins, Vec::new());
+        assert_eq!(
+            diff.renamed_plugins,
+            vec![Change {
+                old: plugin("56535455564852757632326872000000", "UV22HR"),
+                new: plugin("56535455564852757632326872000000", "Lin Dither"),
+            }]
+        );
+        assert_eq!(diff.channels, Vec::new());
+    }
+
+    #[test]
+    fn test_project_diff_plugins() {
+        let old = read_project("Example Project (Cubase 14).cpr");
+        let mut new = old.clone();
+
+        // Replace Elephant with a plugin of the same name but a different GUID, remove Hive and add
+        // a second StudioEQ to the track which already has one.
+        let elephant_vst2 = plugin("5653544B6E6C3265656C657068616E74", "Elephant");
+        let studio_eq = plugin("946051208E29496E804F64A825C8A047", "StudioEQ");
+        new.plugins
+            .retain(|plugin| plugin.name != "Hive" && plugin.name != "Elephant");
+        new.plugins.insert(elephant_vst2.clone());
+        new.instances
+            .retain(|instance| instance.plugin.name != "Hive");
+        for instance in &mut new.instances {
+            if instance.plugin.name == "Elephant" {
+                instance.plugin = elephant_vst2.clone();
+            }
+        }
+        let mut studio_eq_instance = new
+            .instances
+            .iter()
+            .find(|instance| instance.plugin == studio_eq)
+            .unwrap()
+            .clone();
+        studio_eq_instance.offset += 1;
+        new.instances.push(studio_eq_instance);
+
+        let diff = ProjectDiff::new(&old, &new);
+
+        assert_eq!(diff.version, None);
+        assert_eq!(diff.added_plugins, Vec::new());
+        assert_eq!(
+            diff.removed_plugins,
+            vec![plugin("D39D5B69D6AF42FA1234567868495645", "Hive")]
+        );
+        assert_eq!(
+            diff.renamed_plugins,
+            vec![Change {
+                old: plugin("1C3A662167D347A99F7D797EA4911CDB", "Elephant"),
+                new: elephant_vst2.clone(),
+            }]
+        );
+        assert_eq!(
+            diff.channels,
+            vec![
+                ChannelDiff {
+                    channel: Channel {
+                        name: "Acid Bass".to_string(),
+                        kind: ChannelKind::InstrumentTrack,
+                    },
+                    added: vec![studio_eq],
+                    removed: Vec::new(),
+                },
+                ChannelDiff {
+                    channel: Channel {
+                        name: "Stereo Out".to_string(),
+                        kind: ChannelKind::OutputBus,
+                    },
+                    added: vec![elephant_vst2],
+                    removed: vec![plugin("1C3A662167D347A99F7D797EA4911CDB", "Elephant")],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Identifies the kinds of files which Cubase saves projects to, and the versions of a project
+//! which are saved alongside each other (e.g. `Project.cpr`, `Project-01.cpr` and `Project.bak`).
+
+use std::{fmt, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+/// The extension of Cubase project files.
+pub const PROJECT_EXTENSION: &str = "cpr";
+
+/// The extension of backup project files which Cubase writes when a project is saved.
+pub const BACKUP_EXTENSION: &str = "bak";
+
+/// The extension of auto-save project files which Cubase writes periodically while a project is
+/// open.
+pub const AUTO_SAVE_EXTENSION: &str = "csh";
+
+/// The kind of file which a Cubase project was read from, each of which uses the same format.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectFileKind {
+    /// A project file (*.cpr) saved by the user.
+    Project,
+    /// A backup (*.bak) which Cubase wrote when the project was saved.
+    Backup,
+    /// An auto-save copy (*.csh) which Cubase wrote while the project was open.
+    AutoSave,
+}
+
+impl ProjectFileKind {
+    /// Identifies the kind of a project file from its extension, treating files with other
+    /// extensions as project files.
+    #[must_use]
+    pub fn from_path(project_file_path: &Path) -> Self {
+        let Some(extension) = project_file_path.extension() else {
+            return Self::Project;
+        };
+
+        if extension.eq_ignore_ascii_case(BACKUP_EXTENSION) {
+            Self::Backup
+        } else if extension.eq_ignore_ascii_case(AUTO_SAVE_EXTENSION) {
+            Self::AutoSave
+        } else {
+            Self::Project
+        }
+    }
+
+    /// The extension of files of this kind.
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Project => PROJECT_EXTENSION,
+            Self::Backup => BACKUP_EXTENSION,
+            Self::AutoSave => AUTO_SAVE_EXTENSION,
+        }
+    }
+}
+
+impl fmt::Display for ProjectFileKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Self::Project => "project",
+            Self::Backup => "backup",
+            Self::AutoSave => "auto-save",
+        };
+        f.write_str(description)
+    }
+}
+
+/// Prefixes of the folders in which Cubase stores older versions and automatic backups of a
+/// project, which belong to the project folder containing them.
+const VERSION_FOLDER_PREFIXES: &[&str] = &["Older Versions", "Auto Saves"];
+
+/// A version of a Cubase project as identified from the name of its file.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ProjectVersion {
+    /// Name of the project without its version suffix or extension (e.g. `Project` for
+    /// `Project-01.cpr`).
+    pub base_name: String,
+    /// Version number from the suffix of the file name (e.g. 1 for `Project-01.cpr`), or 0 when
+    /// the file name has no suffix.
+    pub number: u32,
+    /// Whether the file is a backup (*.bak) which Cubase wrote when the project was saved.
+    pub backup: bool,
+}
+
+impl ProjectVersion {
+    /// Identifies the version of a project from the path of its file.
+    #[must_use]
+    pub fn from_path(project_file_path: &Path) -> Self {
+        let file_stem = project_file_path
+            .file_stem()
+            .map(|file_stem| file_stem.to_string_lossy())
+            .unwrap_or_default();
+        let backup = ProjectFileKind::from_path(project_file_path) == ProjectFileKind::Backup;
+
+        let (base_name, number) = file_stem
+            .rsplit_once('-')
+            .filter(|(base_name, suffix)| {
+                !base_name.is_empty()
+                    && !suffix.is_empty()
+                    && suffix.bytes().all(|byte| byte.is_ascii_digit())
+            })
+            .and_then(|(base_name, suffix)| Some((base_name, suffix.parse().ok()?)))
+            .unwrap_or((&file_stem, 0));
+
+        Self {
+            base_name: base_name.to_string(),
+            number,
+            backup,
+        }
+    }
+
+    /// Determines the order of versions, with each backup preceding the project file saved over it.
+    #[must_use]
+    pub const fn sort_key(&self) -> (u32, bool) {
+        (self.number, !self.backup)
+    }
+}
+
+/// Determines the folder of the project which a project file belongs to, which is the folder
+/// containing the file unless it's a folder of older versions or automatic backups.
+#[must_use]
+pub fn project_folder(project_file_path: &Path) -> &Path {
+    let Some(folder) = project_file_path.parent() else {
+        return Path::new("");
+    };
+
+    let is_version_folder = folder.file_name().is_some_and(|folder_name| {
+        let folder_name = folder_name.to_string_lossy();
+        VERSION_FOLDER_PREFIXES
+            .iter()
+            .any(|prefix| folder_name.starts_with(prefix))
+    });
+    match folder.parent() {
+        Some(project_folder) if is_version_folder => project_folder,
+        _ => folder,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    #[rstest]
+    #[case::project("Song/Project.cpr", ProjectFileKind::Project)]
+    #[case::project_uppercase("Song/Project.CPR", ProjectFileKind::Project)]
+    #[case::backup("Song/Project.bak", ProjectFileKind::Backup)]
+    #[case::auto_save("Song/Auto Saves/Project-01.csh", ProjectFileKind::AutoSave)]
+    #[case::auto_save_uppercase("Song/Auto Saves/Project-01.CSH", ProjectFileKind::AutoSave)]
+    #[case::no_extension("Song/Project", ProjectFileKind::Project)]
+    fn test_project_file_kind_from_path(
+        #[case] project_file_path: &str,
+        #[case] expected: ProjectFileKind,
+    ) {
+        assert_eq!(
+            ProjectFileKind::from_path(Path::new(project_file_path)),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::original("Song/Project.cpr", "Project", 0, false)]
+    #[case::version("Song/Project-01.cpr", "Project", 1, false)]
+    #[case::later_version("Song/Project-12.CPR", "Project", 12, false)]
+    #[case::backup("Song/Project.bak", "Project", 0, true)]
+    #[case::version_backup("Song/Project-02.BAK", "Project", 2, true)]
+    #[case::hyphenated_name("Song/My-Project-03.cpr", "My-Project", 3, false)]
+    #[case::non_numeric_suffix("Song/Project-Mix.cpr", "Project-Mix", 0, false)]
+    #[case::empty_suffix("Song/Project-.cpr", "Project-", 0, false)]
+    #[case::only_suffix("Song/-01.cpr", "-01", 0, false)]
+    fn test_project_version_from_path(
+        #[case] project_file_path: &str,
+        #[case] base_name: &str,
+        #[case] number: u32,
+        #[case] backup: bool,
+    ) {
+        assert_eq!(
+            ProjectVersion::from_path(Path::new(project_file_path)),
+            ProjectVersion {
+                base_name: base_name.to_string(),
+                number,
+                backup,
+            }
+        );
+    }
+
+    #[test]
+    fn test_project_version_sort_key() {
+        let mut versions = [
+            "Project-02.cpr",
+            "Project-01.cpr",
+            "Project.cpr",
+            "Project-01.bak",
+            "Project.bak",
+        ]
+        .map(|file_name| ProjectVersion::from_path(Path::new(file_name)));
+        versions.sort_by_key(ProjectVersion::sort_key);
+
+        assert_eq!(
+            versions
+                .iter()
+                .map(|version| (version.number, version.backup))
+                .collect::<Vec<_>>(),
+            vec![(0, true), (0, false), (1, true), (1, false), (2, false)]
+        );
+    }
+
+    #[rstest]
+    #[case::project_folder("Songs/Song/Project.cpr", "Songs/Song")]
+    #[case::older_versions("Songs/Song/Older Versions/Project-01.cpr", "Songs/Song")]
+    #[case::older_versions_suffix("Songs/Song/Older Versions 2/Project-01.cpr", "Songs/Song")]
+    #[case::auto_saves("Songs/Song/Auto Saves/Project-01.bak", "Songs/Song")]
+    #[case::file_name_only("Project.cpr", "")]
+    fn test_project_folder(#[case] project_file_path: &str, #[case] expected: &str) {
+        assert_eq!(
+            project_folder(Path::new(project_file_path)),
+            Path::new(expected)
+        );
+    }
+}
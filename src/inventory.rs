@@ -0,0 +1,343 @@
+use std::{
+    collections::HashSet,
+    fs, mem,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Error, Result, bail};
+use colored::Colorize as _;
+use glob::MatchOptions;
+use serde::{Deserialize, Serialize};
+
+/// The category of VST3 classes which are plugins rather than controllers or other components.
+const VST3_AUDIO_MODULE_CATEGORY: &str = "Audio Module Class";
+
+/// Whether a plugin used in a project is available on this machine.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginStatus {
+    /// A plugin with the same GUID is installed.
+    Installed,
+    /// A plugin with the same name is installed but its GUID differs.
+    NameOnly,
+    /// No plugin with the same GUID or name is installed.
+    Missing,
+}
+
+/// A single installed plugin listed in an inventory file.
+#[derive(Debug, Deserialize)]
+struct InventoryPlugin {
+    /// Globally unique identifier for the plugin.
+    guid: String,
+    /// Name of the plugin.
+    name: String,
+}
+
+/// An inventory file listing installed plugins.
+#[derive(Debug, Deserialize)]
+struct InventoryFile {
+    /// Plugins which are installed.
+    plugins: Vec<InventoryPlugin>,
+}
+
+/// The compatibility of a VST3 class with plugins it replaces.
+#[derive(Debug, Deserialize)]
+struct ModuleInfoCompatibility {
+    /// The class ID of the replacement plugin.
+    #[serde(rename = "New")]
+    new: String,
+    /// The IDs of plugins which are replaced, such as VST2 versions of the plugin.
+    #[serde(rename = "Old")]
+    old: Vec<String>,
+}
+
+/// A class exported by a VST3 module.
+#[derive(Debug, Deserialize)]
+struct ModuleInfoClass {
+    /// The class ID.
+    #[serde(rename = "CID")]
+    cid: String,
+    /// The category of the class.
+    #[serde(rename = "Category")]
+    category: String,
+    /// Name of the class.
+    #[serde(rename = "Name")]
+    name: String,
+}
+
+/// The contents of a `moduleinfo.json` file within a VST3 bundle, which uses the JSON5 format.
+#[derive(Debug, Deserialize)]
+struct ModuleInfo {
+    /// Classes exported by the module.
+    #[serde(rename = "Classes")]
+    classes: Vec<ModuleInfoClass>,
+    /// Plugins which are replaced by classes in the module.
+    #[serde(rename = "Compatibility", default)]
+    compatibility: Vec<ModuleInfoCompatibility>,
+}
+
+/// The plugins installed on a machine, used to determine which plugins projects are missing.
+#[derive(Debug, Default)]
+pub struct Inventory {
+    /// GUIDs of installed plugins in uppercase.
+    guids: HashSet<String>,
+    /// Names of installed plugins in lowercase.
+    names: HashSet<String>,
+    /// Module info files which were skipped since they couldn't be read, along with the reason.
+    errors: Vec<(PathBuf, Error)>,
+}
+
+impl Inventory {
+    /// Loads an inventory from a TOML or JSON file listing installed plugins, or by scanning a
+    /// directory of VST3 plugins for `moduleinfo.json` files.
+    pub fn load(inventory_path: &Path) -> Result<Self> {
+        if inventory_path.is_dir() {
+            return Self::scan_vst3_directory(inventory_path);
+        }
+
+        let inventory_string = fs::read_to_string(inventory_path).with_context(|| {
+            format!(
+                "unable to open and read inventory file '{}'",
+                inventory_path.display().to_string().blue()
+            )
+        })?;
+
+        let is_json = inventory_path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        let inventory_file: InventoryFile = if is_json {
+            serde_json::from_str(&inventory_string).map_err(Error::from)
+        } else {
+            toml::from_str(&inventory_string).map_err(Error::from)
+        }
+        .with_context(|| {
+            format!(
+                "unable to parse inventory file '{}'",
+                inventory_path.display().to_string().blue()
+            )
+        })?;
+
+        let mut inventory = Self::default();
+        for plugin in inventory_file.plugins {
+            inventory.add(&plugin.guid, &plugin.name);
+        }
+        Ok(inventory)
+    }
+
+    fn scan_vst3_directory(vst3_path: &Path) -> Result<Self> {
+        let module_info_path_pattern = vst3_path.join("**").join("moduleinfo.json");
+        let Some(module_info_path_pattern) = module_info_path_pattern.to_str() else {
+            bail!("unable to convert the module info file pattern to a string");
+        };
+
+        let module_info_paths = glob::glob_with(
+            module_info_path_pattern,
+            MatchOptions {
+                case_sensitive: false,
+                require_literal_separator: false,
+                require_literal_leading_dot: false,
+            },
+        )
+        .context("unable to glob for module info files in the inventory path")?;
+
+        let mut inventory = Self::default();
+        for module_info_path in module_info_paths {
+            let module_info_path = module_info_path
+                .context("unable to glob a particular module info file in the inventory path")?;
+
+            match read_module_info(&module_info_path) {
+                Ok(module_info) => inventory.add_module_info(&module_info),
+                Err(error) => inventory.errors.push((module_info_path, error)),
+            }
+        }
+        Ok(inventory)
+    }
+
+    /// Adds the plugins exported by a VST3 module along with those which they replace.
+    fn add_module_info(&mut self, module_info: &ModuleInfo) {
+        for class in &module_info.classes {
+            if class.category == VST3_AUDIO_MODULE_CATEGORY {
+                self.add(&class.cid, &class.name);
+            }
+        }
+
+        // Plugins which have been replaced by a class in this module (such as the VST2 version
+        // of a plugin) are opened using the replacement, so they are also available.
+        for compatibility in &module_info.compatibility {
+            let Some(class) = module_info
+                .classes
+                .iter()
+                .find(|class| class.cid.eq_ignore_ascii_case(&compatibility.new))
+            else {
+                continue;
+            };
+
+            for old in &compatibility.old {
+                self.add(old, &class.name);
+            }
+        }
+    }
+
+    fn add(&mut self, guid: &str, name: &str) {
+        self.guids.insert(guid.to_uppercase());
+        self.names.insert(name.to_lowercase());
+    }
+
+    /// Takes the module info files which were skipped while scanning a directory of VST3 plugins
+    /// along with the reason each was skipped.
+    pub fn take_errors(&mut self) -> Vec<(PathBuf, Error)> {
+        mem::take(&mut self.errors)
+    }
+
+    /// Determines whether a plugin with the given GUID and name is installed.
+    pub fn status(&self, guid: &str, name: &str) -> PluginStatus {
+        if self.guids.contains(&guid.to_uppercase()) {
+            PluginStatus::Installed
+        } else if self.names.contains(&name.to_lowercase()) {
+            PluginStatus::NameOnly
+        } else {
+            PluginStatus::Missing
+        }
+    }
+}
+
+/// Reads and parses a `moduleinfo.json` file, which may contain comments and trailing commas.
+fn read_module_info(module_info_path: &Path) -> Result<ModuleInfo> {
+    let module_info_string =
+        fs::read_to_string(module_info_path).context("unable to read module info file")?;
+    json5::from_str(&module_info_string).context("unable to parse module info file")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use rstest::rstest;
+    use similar_asserts::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const HIVE_GUID: &str = "D39D5B69D6AF42FA1234567868495645";
+    const SYLENTH1_GUID: &str = "56535473796C3173796C656E74683100";
+
+    const MODULE_INFO: &str = r#"{
+  // Comments and trailing commas are allowed in module info files.
+  "Name": "Hive 2",
+  "Classes": [
+    {
+      "CID": "D39D5B69D6AF42FA1234567868495645",
+      "Category": "Audio Module Class",
+      "Name": "Hive",
+    },
+    {
+      "CID": "D39D5B69D6AF42FA1234567868495646",
+      "Category": "Component Controller Class",
+      "Name": "Hive Controller",
+    },
+  ],
+  "Compatibility": [
+    {
+      "New": "d39d5b69d6af42fa1234567868495645",
+      "Old": ["56535448763248763200000000000000"],
+    },
+  ],
+}"#;
+
+    fn write_file(directory: &TempDir, file_name: &str, contents: &str) -> PathBuf {
+        let path = directory.path().join(file_name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[rstest]
+    #[case::toml(
+        "inventory.toml",
+        "[[plugins]]\nguid = \"D39D5B69D6AF42FA1234567868495645\"\nname = \"Hive\"\n"
+    )]
+    #[case::json(
+        "inventory.JSON",
+        r#"{"plugins": [{"guid": "D39D5B69D6AF42FA1234567868495645", "name": "Hive"}]}"#
+    )]
+    fn test_load_file(#[case] file_name: &str, #[case] contents: &str) {
+        let directory = TempDir::new().unwrap();
+        let inventory_path = write_file(&directory, file_name, contents);
+
+        let inventory = Inventory::load(&inventory_path).unwrap();
+
+        assert_eq!(inventory.status(HIVE_GUID, "Hive"), PluginStatus::Installed);
+        assert_eq!(
+            inventory.status(SYLENTH1_GUID, "Sylenth1"),
+            PluginStatus::Missing
+        );
+    }
+
+    #[test]
+    fn test_load_invalid_file() {
+        let directory = TempDir::new().unwrap();
+        let inventory_path = write_file(&directory, "inventory.toml", "plugins = 1");
+
+        let error = Inventory::load(&inventory_path).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .starts_with("unable to parse inventory file")
+        );
+    }
+
+    #[test]
+    fn test_load_vst3_directory() {
+        let directory = TempDir::new().unwrap();
+        write_file(
+            &directory,
+            "Hive.vst3/Contents/Resources/moduleinfo.json",
+            MODULE_INFO,
+        );
+        let invalid_path = write_file(
+            &directory,
+            "Broken.vst3/Contents/Resources/moduleinfo.json",
+            "{ \"Classes\": ",
+        );
+
+        let mut inventory = Inventory::load(directory.path()).unwrap();
+
+        assert_eq!(inventory.status(HIVE_GUID, "Hive"), PluginStatus::Installed);
+        assert_eq!(
+            inventory.status("56535448763248763200000000000000", "Hive"),
+            PluginStatus::Installed
+        );
+        assert_eq!(
+            inventory.status("D39D5B69D6AF42FA1234567868495646", "Hive Controller"),
+            PluginStatus::Missing
+        );
+        let errors = inventory.take_errors();
+        assert_eq!(
+            errors
+                .iter()
+                .map(|(path, error)| (path.clone(), error.to_string()))
+                .collect::<Vec<_>>(),
+            vec![(invalid_path, "unable to parse module info file".to_string())]
+        );
+        assert!(inventory.take_errors().is_empty());
+    }
+
+    #[rstest]
+    #[case::same_guid(HIVE_GUID, "Hive", PluginStatus::Installed)]
+    #[case::same_guid_lowercase(
+        "d39d5b69d6af42fa1234567868495645",
+        "Hive",
+        PluginStatus::Installed
+    )]
+    #[case::same_guid_renamed(HIVE_GUID, "Hive 2", PluginStatus::Installed)]
+    #[case::same_name("D39D5B69D6AF42FA0000000000000000", "HIVE", PluginStatus::NameOnly)]
+    #[case::missing(SYLENTH1_GUID, "Sylenth1", PluginStatus::Missing)]
+    fn test_status(#[case] guid: &str, #[case] name: &str, #[case] expected: PluginStatus) {
+        let mut inventory = Inventory::default();
+        inventory.add(HIVE_GUID, "Hive");
+
+        assert_eq!(inventory.status(guid, name), expected);
+    }
+}
@@ -0,0 +1,35 @@
+//! Reads Cubase project files (*.cpr) and determines the version of Cubase used to create each
+//! project along with all the plugins it uses.
+//!
+//! ```
+//! use std::fs;
+//!
+//! use cubase_project_plugins::Reader;
+//!
+//! let project_bytes = fs::read("testdata/Example Project (Cubase 14).cpr")?;
+//! let project = Reader::new(&project_bytes).get_project_details()?;
+//!
+//! assert_eq!(project.metadata.application, "Cubase");
+//! assert_eq!(project.metadata.version, "14.0.30");
+//! assert!(project.plugins.iter().any(|plugin| plugin.name == "Sylenth1"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+pub mod archive;
+pub mod attributes;
+pub mod diff;
+pub mod history;
+pub mod project;
+pub mod reader;
+pub mod steinberg;
+
+pub use crate::{
+    archive::Archive,
+    diff::ProjectDiff,
+    history::{ProjectFileKind, ProjectVersion},
+    project::{
+        Channel, ChannelKind, MediaFile, Metadata, Plugin, PluginCategory, PluginFormat,
+        PluginInstance, PluginState, Project, Tempo, TimeSignature, Track, TrackKind,
+    },
+    reader::Reader,
+};
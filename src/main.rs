@@ -1,25 +1,80 @@
+mod bundle;
+mod cache;
+mod catalog;
 mod cli;
 mod config;
-mod project;
-mod reader;
+mod inventory;
+mod preset;
+mod report;
 
 use std::{
-    collections::HashMap,
+    borrow::Cow,
+    collections::{BTreeSet, HashMap, HashSet},
     fs::{self, File},
-    io::{self, Read},
-    path::Path,
+    hash::Hash,
+    io::{self, Read, Write},
+    iter,
+    num::NonZeroUsize,
+    path::{self, Component, Path, PathBuf},
     process,
+    sync::mpsc,
+    time::{Duration, SystemTime},
 };
 
 use anyhow::{Context, Error, Result, anyhow, bail};
-use clap::{CommandFactory as _, Parser as _};
-use colored::Colorize as _;
-use glob::{MatchOptions, Pattern};
+use clap::CommandFactory as _;
+use clap_complete::Shell;
+use colored::{ColoredString, Colorize as _};
+use cubase_project_plugins::{
+    Channel, MediaFile, Metadata, Plugin, PluginCategory, PluginInstance, Project, ProjectDiff,
+    ProjectFileKind, ProjectVersion, Reader, Track, history,
+};
+use glob::{GlobResult, MatchOptions, Pattern};
+use notify::{Event, EventKind, RecursiveMode, Watcher as _, event::ModifyKind};
+use rayon::{ThreadPool, ThreadPoolBuilder, prelude::*};
 use wildmatch::{WildMatch, WildMatchPattern};
 
-use crate::{cli::Cli, config::Config, project::Plugin, reader::Reader};
+use crate::{
+    cache::{Cache, FileStamp},
+    catalog::Catalog,
+    cli::{
+        Cli, Command, DiffArgs, DiffFormat, Format, HistoryArgs, Inactive, InspectArgs,
+        ProjectOptions, ScanArgs, SortOrder, StateEncoding, StateOptions, SummaryArgs,
+        VersionOrder,
+    },
+    config::Config,
+    inventory::{Inventory, PluginStatus},
+    preset::PresetDirectories,
+    report::{
+        CategoryCount, CubaseVersionCount, DiffReport, ErrorReport, HistoryReport, MediaReport,
+        MissingPluginCount, PluginCount, PluginReport, PluginUsage, PluginUsageRecord,
+        ProjectHistoryReport, ProjectReport, ProjectUsageRecord, ProjectUsageReport,
+        ProjectVersionReport, Report, SampleRateCount, StateReport, Summaries, SummaryRecord,
+        UNKNOWN_VENDOR, VendorCount, Vst2PluginCount,
+    },
+};
+
+/// The number of project files which are read and parsed concurrently before their results are
+/// displayed.
+const PROJECT_BATCH_SIZE: usize = 256;
+
+/// How long to wait for further file system events before rescanning changed projects in watch
+/// mode.
+const WATCH_DEBOUNCE_DURATION: Duration = Duration::from_millis(500);
 
-fn print_error(error: &Error) {
+/// The project path which reads a project or a list of project paths from standard input.
+const STDIN_PATH: &str = "-";
+
+/// The path which a project read from standard input is reported with.
+const STDIN_PROJECT_PATH: &str = "<stdin>";
+
+/// The headers which project files begin with, distinguishing a project read from standard input
+/// from a list of project paths.
+const PROJECT_HEADERS: &[&[u8]] = &[b"RIFF", b"RIF2"];
+
+/// Prints an error to stderr followed by its causes, which are written to the given stream so that
+/// they may also be kept out of stdout when it contains JSON or CSV output.
+fn print_error(error: &Error, causes: &mut dyn Write) {
     for (index, cause) in error.chain().enumerate() {
         if index == 0 {
             eprintln!("{}: {cause}", "error".red());
@@ -29,322 +84,3146 @@ fn print_error(error: &Error) {
         if index == 1 {
             eprintln!("{}", "caused by:".red());
         }
-        println!("    {}: {cause}", index - 1);
+        // Errors can't be reported if the stream itself can't be written to.
+        let _ = writeln!(causes, "    {}: {cause}", index - 1);
     }
 }
 
-fn main() {
-    if let Err(error) = run() {
-        print_error(&error);
-        process::exit(1);
+/// Prints a heading containing the path of a project or directory, labelling backup and auto-save
+/// files.
+fn print_path_heading(path: &Path) {
+    let path_heading = match ProjectFileKind::from_path(path) {
+        ProjectFileKind::Project => format!("Path: {}", path.display()),
+        kind => format!("Path: {} [{kind}]", path.display()),
     }
+    .white()
+    .on_red();
+    println!();
+    println!("{path_heading}");
+    println!();
 }
 
-fn run() -> Result<()> {
-    // Parse CLI arguments.
-    let cli = Cli::parse();
+fn print_path_error(path: &Path, error: &Error) {
+    eprintln!("{}: {}", "path".red(), path.display());
+    print_error(error, &mut io::stderr());
+}
 
-    // Display shell completions.
-    if let Some(shell) = cli.completions {
-        let mut cmd = Cli::command();
-        clap_complete::generate(shell, &mut cmd, env!("CARGO_PKG_NAME"), &mut io::stdout());
-        return Ok(());
-    }
+/// Reads and parses a project file, which may be within a zip or tar archive when its path includes
+/// the path of the archive (e.g. `Projects.zip!/Song/Song.cpr`).
+fn read_cubase_project_file(project_file_path: &Path, read_states: bool) -> Result<Project> {
+    let project_bytes = if let Some((bundle_path, entry_name)) =
+        bundle::split_entry_path(project_file_path)
+    {
+        bundle::read_bundle_entry(bundle_path, entry_name).context("unable to read project file")?
+    } else {
+        let mut file = File::open(project_file_path).context("unable to open project file")?;
+        let mut project_bytes = Vec::new();
+        file.read_to_end(&mut project_bytes)
+            .context("unable to read project file")?;
+        project_bytes
+    };
 
-    // Load the user config.
-    let config_path = cli
-        .config_path
-        .or_else(|| match cli::default_config_path() {
-            Some(default_config_path) if default_config_path.is_file() => Some(default_config_path),
-            _ => None,
-        });
+    parse_cubase_project(&project_bytes, read_states)
+}
 
-    let config = match config_path {
-        Some(config_path) => {
-            let config_string = fs::read_to_string(&config_path).with_context(|| {
-                format!(
-                    "unable to open and read config file '{}'",
-                    config_path.display().to_string().blue()
-                )
-            })?;
+/// Parses the contents of a project file.
+fn parse_cubase_project(project_bytes: &[u8], read_states: bool) -> Result<Project> {
+    let mut reader = Reader::new(project_bytes);
+    if read_states {
+        reader = reader.with_plugin_states();
+    }
+    reader
+        .get_project_details()
+        .context("unable to parse project file")
+}
 
-            toml::from_str(&config_string).with_context(|| {
-                format!(
-                    "unable to parse config file '{}'",
-                    config_path.display().to_string().blue()
-                )
-            })?
-        }
-        None => Config::default(),
-    };
+/// Determines whether a project path is a glob pattern rather than a literal path.
+fn is_glob_pattern(project_path: &str) -> bool {
+    project_path.contains(['*', '?', '['])
+}
 
-    // Process Cubase project files.
-    let path_ignore_globs = config
-        .path_ignore_patterns
-        .iter()
-        .map(|pattern| {
-            Pattern::new(pattern).with_context(|| {
-                format!("unable to parse path ignore pattern '{}'", pattern.blue())
-            })
-        })
-        .collect::<Result<Vec<_>>>()?;
+/// Reads everything from standard input.
+fn read_stdin() -> Result<Vec<u8>> {
+    let mut stdin_bytes = Vec::new();
+    io::stdin()
+        .lock()
+        .read_to_end(&mut stdin_bytes)
+        .context("unable to read from standard input")?;
+    Ok(stdin_bytes)
+}
 
-    let mut processor = Processor::new(
-        config,
-        path_ignore_globs,
-        cli.patterns,
-        cli.only_show_filtered,
-    );
+/// A project file or archive to read, or a project which was read from standard input.
+enum ProjectSource {
+    /// Path of a project file or archive.
+    Path(PathBuf),
+    /// The contents of a project file which was read from standard input.
+    Stdin(Vec<u8>),
+}
 
-    for project_path in &cli.project_paths {
-        let project_path = Path::new(project_path);
-        if !project_path.is_dir() {
-            let project_path_heading = format!("Path: {}", project_path.display()).white().on_red();
-            println!();
-            println!("{project_path_heading}");
-            println!();
-            print_error(&anyhow!("the directory path does not exist"));
-            continue;
-        }
+/// The project files and archives to read, in the order they were found and without duplicates.
+#[derive(Default)]
+struct ProjectFileSet {
+    /// The project files and archives, along with any project read from standard input.
+    sources: Vec<ProjectSource>,
+    /// Canonical paths of the project files and archives, used to skip those which are found
+    /// through several project paths.
+    canonical_paths: HashSet<PathBuf>,
+}
 
-        if let Err(error) = processor.process_cubase_project_path(project_path) {
-            let project_path_heading = format!("Path: {}", project_path.display()).white().on_red();
-            println!();
-            println!("{project_path_heading}");
-            println!();
-            print_error(&error);
+impl ProjectFileSet {
+    /// Adds a project file or archive unless it has already been found.
+    fn insert(&mut self, path: PathBuf) {
+        let canonical_path = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if self.canonical_paths.insert(canonical_path) {
+            self.sources.push(ProjectSource::Path(path));
         }
     }
 
-    processor.print_summaries();
+    /// Adds a project which was read from standard input.
+    fn insert_stdin(&mut self, project_bytes: Vec<u8>) {
+        self.sources.push(ProjectSource::Stdin(project_bytes));
+    }
+}
 
-    Ok(())
+/// A project which was read from a project file or from a file within an archive.
+struct ReadProject {
+    /// Path of the project file.
+    path: PathBuf,
+    /// The parsed project.
+    project: Result<Project>,
+    /// The size and modification time of the project file when the project should be added to the
+    /// cache.
+    stamp: Option<FileStamp>,
+    /// Time the project file was last modified when it was read from an archive.
+    entry_modified: Option<SystemTime>,
 }
 
-struct Processor {
-    config: Config,
-    path_ignore_globs: Vec<Pattern>,
-    filter_patterns: Vec<WildMatchPattern<'*', '?'>>,
-    project_bytes: Vec<u8>,
-    plugin_counts_32: HashMap<Plugin, i32>,
-    plugin_counts_64: HashMap<Plugin, i32>,
-    plugin_counts: HashMap<Plugin, i32>,
-    cubase_version_counts: HashMap<String, i32>,
-    only_show_filtered: bool,
+/// The directories and project files which are watched for changes in watch mode.
+#[derive(Debug, Default)]
+struct WatchTargets {
+    /// Directories which are watched along with everything within them, along with their
+    /// absolute paths which changes within them are reported with.
+    directories: Vec<(PathBuf, PathBuf)>,
+    /// Project files keyed by their canonical path. These are watched through the directory
+    /// containing them, which reports changes using canonical paths.
+    project_files: HashMap<PathBuf, PathBuf>,
 }
 
-impl Processor {
-    pub fn new(
-        config: Config,
-        path_ignore_globs: impl IntoIterator<Item = Pattern>,
-        filter_patterns: impl IntoIterator<Item = String>,
-        only_show_filtered: bool,
-    ) -> Self {
-        Self {
-            config,
-            path_ignore_globs: path_ignore_globs.into_iter().collect(),
-            filter_patterns: filter_patterns
-                .into_iter()
-                .map(|pattern| WildMatch::new_case_insensitive(&pattern))
-                .collect::<Vec<_>>(),
-            project_bytes: Vec::new(),
-            plugin_counts_32: HashMap::new(),
-            plugin_counts_64: HashMap::new(),
-            plugin_counts: HashMap::new(),
-            cubase_version_counts: HashMap::new(),
-            only_show_filtered,
+impl WatchTargets {
+    /// Adds a directory or project file to watch.
+    fn insert(&mut self, path: &Path) {
+        if path.is_dir() {
+            if let Ok(absolute_path) = path::absolute(path) {
+                self.directories.push((path.to_path_buf(), absolute_path));
+            }
+        } else if let Some(canonical_path) = canonical_file_path(path) {
+            self.project_files
+                .insert(canonical_path, path.to_path_buf());
         }
     }
 
-    pub fn process_cubase_project_path(&mut self, project_path: &Path) -> Result<()> {
-        let project_file_path_pattern = project_path.join("**").join("*.cpr");
-        let Some(project_file_path_pattern) = project_file_path_pattern.to_str() else {
-            bail!("unable to convert the project file pattern to a string");
-        };
+    /// Determines whether nothing is watched.
+    fn is_empty(&self) -> bool {
+        self.directories.is_empty() && self.project_files.is_empty()
+    }
 
-        let project_file_paths = glob::glob_with(
-            project_file_path_pattern,
-            MatchOptions {
-                case_sensitive: false,
-                require_literal_separator: false,
-                require_literal_leading_dot: false,
-            },
-        )
-        .context("unable to glob for project files in the project path")?;
+    /// Determines the directories containing the watched project files.
+    fn project_file_directories(&self) -> BTreeSet<&Path> {
+        self.project_files
+            .keys()
+            .filter_map(|canonical_path| canonical_path.parent())
+            .collect()
+    }
 
-        for project_file_path in project_file_paths {
-            let project_file_path = match project_file_path {
-                Ok(project_file_path) => project_file_path,
-                Err(error) => {
-                    let error = Error::from(error)
-                        .context("unable to glob a particular project file in the project path");
-                    print_error(&error);
-                    continue;
-                }
-            };
+    /// Converts a path reported by the file watcher into the path it was scanned with, skipping
+    /// files which were only reported because they are alongside a watched project file.
+    fn resolve(&self, path: &Path) -> Option<PathBuf> {
+        if let Some(project_file_path) = canonical_file_path(path)
+            .and_then(|canonical_path| self.project_files.get(&canonical_path))
+        {
+            return Some(project_file_path.clone());
+        }
 
-            if self
-                .path_ignore_globs
-                .iter()
-                .any(|glob| glob.matches_path(&project_file_path))
-            {
-                continue;
-            }
+        // Paths found by searching directories don't include any `.` components.
+        self.directories
+            .iter()
+            .find_map(|(directory, absolute_directory)| {
+                let relative_path = path.strip_prefix(absolute_directory).ok()?;
+                Some(
+                    directory
+                        .join(relative_path)
+                        .components()
+                        .filter(|component| *component != Component::CurDir)
+                        .collect(),
+                )
+            })
+    }
+}
 
-            if let Err(error) = self.process_cubase_project_file(&project_file_path) {
-                print_error(&error);
-            }
-        }
+/// Determines the canonical path of a file by resolving the directory containing it, so that the
+/// path can be determined after the file is removed.
+fn canonical_file_path(path: &Path) -> Option<PathBuf> {
+    let directory = path
+        .parent()
+        .filter(|directory| !directory.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    Some(fs::canonicalize(directory).ok()?.join(path.file_name()?))
+}
 
-        Ok(())
+/// Reads and parses each project file with the given extensions within a zip or tar archive in
+/// memory. Archives can't be cached as a whole, so they are read every time.
+fn read_bundle_projects(
+    bundle_path: &Path,
+    extensions: &[&str],
+    read_states: bool,
+) -> Vec<ReadProject> {
+    match bundle::read_bundle_entries(bundle_path, extensions) {
+        Ok(entries) => entries
+            .into_iter()
+            .map(|entry| ReadProject {
+                path: entry.path,
+                project: entry
+                    .bytes
+                    .context("unable to read project file")
+                    .and_then(|bytes| parse_cubase_project(&bytes, read_states)),
+                stamp: None,
+                entry_modified: entry.modified,
+            })
+            .collect(),
+        Err(error) => vec![ReadProject {
+            path: bundle_path.to_path_buf(),
+            project: Err(error),
+            stamp: None,
+            entry_modified: None,
+        }],
     }
+}
 
-    fn matches_filters(&self, plugin: &Plugin) -> bool {
-        self.filter_patterns.is_empty()
-            || self
-                .filter_patterns
-                .iter()
-                .any(|pattern| pattern.matches(&plugin.name) || pattern.matches(&plugin.guid))
+/// Reads and parses a project file unless it is unchanged since it was cached, also returning the
+/// size and modification time of the file when the project should be added to the cache.
+///
+/// Cached projects don't include plugin states, so the cache is only used when they aren't read.
+fn read_cubase_project_file_cached(
+    project_file_path: &Path,
+    cache: Option<&Cache>,
+    read_states: bool,
+) -> (Result<Project>, Option<FileStamp>) {
+    let Some(cache) = cache.filter(|_| !read_states) else {
+        return (
+            read_cubase_project_file(project_file_path, read_states),
+            None,
+        );
+    };
+
+    let Ok(stamp) = FileStamp::new(project_file_path) else {
+        return (read_cubase_project_file(project_file_path, false), None);
+    };
+
+    if let Some(project) = cache.get(project_file_path, stamp) {
+        return (Ok(project.clone()), None);
     }
 
-    fn process_cubase_project_file(&mut self, project_file_path: &Path) -> Result<()> {
-        let mut file = File::open(project_file_path).context("unable to open project file")?;
-        file.read_to_end(&mut self.project_bytes)
-            .context("unable to read project file")?;
+    (
+        read_cubase_project_file(project_file_path, false),
+        Some(stamp),
+    )
+}
 
-        let reader = Reader::new(&self.project_bytes);
-        let project_details = reader
-            .get_project_details()
-            .context("unable to parse project file")?;
-        self.project_bytes.clear();
+/// Formats a count followed by a noun which is pluralised when the count isn't one.
+fn pluralise(count: i32, noun: &str) -> String {
+    if count == 1 {
+        format!("{count} {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
 
-        let mut sorted_plugins = Vec::from_iter(project_details.plugins);
-        sorted_plugins.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+/// The instances of a particular plugin within a project.
+#[derive(Default)]
+struct PluginInstances<'a> {
+    /// Number of instances of the plugin.
+    count: i32,
+    /// Number of instances of the plugin which are bypassed or deactivated.
+    inactive: i32,
+    /// Tracks and channels which use the plugin, without duplicates.
+    channels: Vec<&'a Channel>,
+    /// Categories which the plugin is used in along with the number of instances in each, in the
+    /// order they first appear.
+    categories: Vec<(PluginCategory, i32)>,
+    /// Every instance of the plugin, in the order they appear in the project.
+    instances: Vec<&'a PluginInstance>,
+}
 
-        let filtered_plugins = sorted_plugins
+impl PluginInstances<'_> {
+    /// Lists the categories which the plugin is used in.
+    fn category_names(&self) -> impl Iterator<Item = String> {
+        self.categories
             .iter()
-            .filter(|p| !self.config.plugins.guid_ignores.contains(&p.guid))
-            .filter(|p| !self.config.plugins.name_ignores.contains(&p.name))
-            .collect::<Vec<_>>();
+            .map(|(category, _)| category.to_string())
+    }
+}
 
-        if !self.filter_patterns.is_empty()
-            && !filtered_plugins
-                .iter()
-                .any(|plugin| self.matches_filters(plugin))
+/// Sorts plugins by name followed by GUID.
+fn sort_plugins(plugins: impl IntoIterator<Item = Plugin>) -> Vec<Plugin> {
+    let mut sorted_plugins = Vec::from_iter(plugins);
+    sorted_plugins.sort_by_cached_key(|plugin| (plugin.name.to_lowercase(), plugin.guid.clone()));
+    sorted_plugins
+}
+
+/// Groups plugin instances by plugin.
+fn group_instances(instances: &[PluginInstance]) -> HashMap<&Plugin, PluginInstances<'_>> {
+    let mut plugin_instances = HashMap::<&Plugin, PluginInstances<'_>>::new();
+    for instance in instances {
+        let grouped_instances = plugin_instances.entry(&instance.plugin).or_default();
+        grouped_instances.count += 1;
+        if instance.is_inactive() {
+            grouped_instances.inactive += 1;
+        }
+
+        if let Some(channel) = &instance.channel
+            && !grouped_instances.channels.contains(&channel)
         {
-            return Ok(());
+            grouped_instances.channels.push(channel);
         }
 
-        let project_file_path_heading = format!("Path: {}", project_file_path.display())
-            .white()
-            .on_red();
-        println!();
-        println!("{project_file_path_heading}");
-        println!();
+        add_instance_count(&mut grouped_instances.categories, instance.category, 1);
+        grouped_instances.instances.push(instance);
+    }
+    plugin_instances
+}
 
-        let is_64_bit = matches!(
-            project_details.metadata.architecture.as_str(),
-            "WIN64" | "MAC64 LE"
-        );
+/// Adds a number of instances to the count for a category or vendor, keeping them in the order
+/// they are first added.
+fn add_instance_count<K: PartialEq>(
+    instance_counts: &mut Vec<(K, i32)>,
+    key: K,
+    instance_count: i32,
+) {
+    match instance_counts
+        .iter_mut()
+        .find(|(counted_key, _)| *counted_key == key)
+    {
+        Some((_, count)) => *count += instance_count,
+        None => instance_counts.push((key, instance_count)),
+    }
+}
 
-        if is_64_bit && !self.config.projects.report_64_bit
-            || !is_64_bit && !self.config.projects.report_32_bit
-        {
-            return Ok(());
-        }
+/// Removes instances which don't satisfy a predicate, along with plugins which are only used by
+/// such instances.
+fn retain_instances(
+    plugins: &mut HashSet<Plugin>,
+    instances: &mut Vec<PluginInstance>,
+    predicate: impl Fn(&PluginInstance) -> bool,
+) {
+    let removed_plugins = instances
+        .iter()
+        .filter(|instance| !predicate(instance))
+        .map(|instance| instance.plugin.clone())
+        .collect::<HashSet<_>>();
 
-        let cubase_version = format!(
-            "{application} {version} ({architecture})",
-            application = project_details.metadata.application,
-            version = project_details.metadata.version,
-            architecture = project_details.metadata.architecture
-        );
+    instances.retain(|instance| predicate(instance));
+    plugins.retain(|plugin| {
+        !removed_plugins.contains(plugin)
+            || instances.iter().any(|instance| &instance.plugin == plugin)
+    });
+}
 
-        let project_heading = cubase_version.blue();
-        println!("{project_heading}");
+/// Removes a single project's usage of a plugin, category or vendor, dropping it once no projects
+/// use it.
+fn uncount_usage<K: Eq + Hash>(
+    usage_counts: &mut HashMap<K, PluginUsage>,
+    key: &K,
+    instance_count: i32,
+) {
+    let Some(usage) = usage_counts.get_mut(key) else {
+        return;
+    };
 
-        if filtered_plugins.is_empty() {
-            return Ok(());
-        }
+    usage.projects -= 1;
+    usage.instances -= instance_count;
+    if usage.projects == 0 {
+        usage_counts.remove(key);
+    }
+}
 
-        self.cubase_version_counts
-            .entry(cubase_version)
-            .and_modify(|count| *count += 1)
-            .or_insert(1);
+/// Resolves the path of a media file, treating relative paths as relative to the directory
+/// containing the project file.
+fn resolve_media_path(project_file_path: &Path, media_file: &MediaFile) -> PathBuf {
+    // Projects created on Windows use backslashes which aren't separators on other platforms.
+    let path = if cfg!(windows) {
+        media_file.path.clone()
+    } else {
+        media_file.path.replace('\\', "/")
+    };
 
-        println!();
-        for plugin in filtered_plugins {
-            self.plugin_counts
-                .entry(plugin.clone())
-                .and_modify(|count| *count += 1)
-                .or_insert(1);
-
-            if is_64_bit {
-                self.plugin_counts_64
-                    .entry(plugin.clone())
-                    .and_modify(|count| *count += 1)
-                    .or_insert(1);
-            } else {
-                self.plugin_counts_32
-                    .entry(plugin.clone())
-                    .and_modify(|count| *count += 1)
-                    .or_insert(1);
-            }
+    if media_file.relative {
+        project_file_path
+            .parent()
+            .map_or_else(|| PathBuf::from(&path), |directory| directory.join(&path))
+    } else {
+        PathBuf::from(path)
+    }
+}
 
-            if !self.only_show_filtered || self.matches_filters(plugin) {
-                println!("    > {} : {}", plugin.guid, plugin.name);
+/// Determines where each media file of a project resides and whether it exists.
+fn media_reports(project_file_path: &Path, media: &[MediaFile]) -> Vec<MediaReport> {
+    media
+        .iter()
+        .map(|media_file| {
+            let resolved_path = resolve_media_path(project_file_path, media_file);
+            MediaReport {
+                path: media_file.path.clone(),
+                exists: resolved_path.exists(),
+                resolved_path: resolved_path.display().to_string(),
+                relative: media_file.relative,
             }
+        })
+        .collect()
+}
+
+/// Prints the media files of a project, flagging those which don't exist.
+fn print_media(media_reports: &[MediaReport]) {
+    if media_reports.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", "Media:".blue());
+    for media_report in media_reports {
+        if media_report.exists {
+            println!("    > {}", media_report.resolved_path);
+        } else {
+            println!("    > {} {}", media_report.resolved_path, "[missing]".red());
         }
+    }
+}
 
-        Ok(())
+/// Prints the tracks of a project, indenting those within folders.
+fn print_tracks(tracks: &[Track]) {
+    if tracks.is_empty() {
+        return;
     }
 
-    pub fn print_summaries(&self) {
-        self.print_plugin_summary(&self.plugin_counts_32, "32-bit");
-        self.print_plugin_summary(&self.plugin_counts_64, "64-bit");
-        self.print_plugin_summary(&self.plugin_counts, "all");
-        self.print_cubase_version_summary();
+    println!();
+    println!("{}", "Tracks:".blue());
+    for track in tracks {
+        let indent = "    ".repeat(track.depth);
+        if track.name.is_empty() {
+            println!("    {indent}> {}", track.kind);
+        } else {
+            println!("    {indent}> {} ({})", track.name, track.kind);
+        }
     }
+}
 
-    fn print_plugin_summary(&self, plugin_counts: &HashMap<Plugin, i32>, description: &str) {
-        if plugin_counts.is_empty() {
-            return;
+/// Formats a sample rate in kHz (e.g. 44.1 kHz).
+fn format_sample_rate(sample_rate: u32) -> String {
+    format!("{} kHz", f64::from(sample_rate) / 1000.0)
+}
+
+/// Describes the tempo, time signature, sample rate and bit depth of a project, skipping any
+/// settings which the project doesn't store.
+fn describe_settings(metadata: &Metadata) -> Option<String> {
+    let tempo = metadata.tempo.map(|tempo| {
+        let source = if metadata.tempo_track_active == Some(true) {
+            "tempo track"
+        } else {
+            "fixed"
+        };
+        format!("{tempo} ({source})")
+    });
+
+    let settings = [
+        tempo,
+        metadata
+            .time_signature
+            .map(|time_signature| time_signature.to_string()),
+        metadata.sample_rate.map(format_sample_rate),
+        metadata
+            .bit_depth
+            .map(|bit_depth| format!("{bit_depth}-bit")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+
+    (!settings.is_empty()).then(|| settings.join(", "))
+}
+
+/// Removes a single project from the count for a key, removing the key once no projects remain.
+fn uncount_key(counts: &mut HashMap<String, i32>, key: &str) {
+    if let Some(count) = counts.get_mut(key) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(key);
         }
+    }
+}
 
-        let summary_heading = format!("Summary: Plugins Used In {description} Projects")
-            .white()
-            .on_red();
+/// Prints a summary of the number of projects for each key, sorted naturally by key.
+fn print_count_summary(counts: &HashMap<String, i32>, heading: &str) {
+    if counts.is_empty() {
+        return;
+    }
 
-        println!();
-        println!("{summary_heading}");
-        println!();
+    let summary_heading = heading.white().on_red();
 
-        let mut sorted_plugin_counts = Vec::from_iter(plugin_counts);
-        sorted_plugin_counts.sort_by(|a, b| a.0.name.to_lowercase().cmp(&b.0.name.to_lowercase()));
+    println!();
+    println!("{summary_heading}");
+    println!();
 
-        for (plugin, count) in &sorted_plugin_counts {
-            if !self.only_show_filtered || self.matches_filters(plugin) {
-                println!("    > {} : {} ({count})", plugin.guid, plugin.name);
-            }
+    let mut sorted_counts = Vec::from_iter(counts);
+    sorted_counts.sort_by(|a, b| natord::compare_ignore_case(a.0, b.0));
+
+    for (key, count) in &sorted_counts {
+        println!("    > {key} ({count})");
+    }
+}
+
+/// The counts which a single project contributed to the summaries.
+struct ProjectCounts {
+    /// Cubase version including the application name and architecture.
+    cubase_version: String,
+    /// Sample rate of the project, or "Unspecified" if the project doesn't store it.
+    sample_rate: String,
+    /// Whether the project was created with a 64-bit version of Cubase.
+    is_64_bit: bool,
+    /// Plugins used in the project along with their number of instances.
+    plugins: Vec<(Plugin, i32)>,
+    /// Categories of plugins used in the project along with their number of instances.
+    categories: Vec<(PluginCategory, i32)>,
+    /// Vendors of plugins used in the project along with their number of instances.
+    vendors: Vec<(Option<String>, i32)>,
+}
+
+impl ProjectCounts {
+    /// Adds the instances of a plugin to the counts of its plugin, categories and vendor.
+    fn add_plugin(
+        &mut self,
+        plugin: &Plugin,
+        instances: Option<&PluginInstances<'_>>,
+        vendor: Option<&str>,
+    ) {
+        let instance_count = instances.map_or(0, |instances| instances.count);
+        self.plugins.push((plugin.clone(), instance_count));
+        for &(category, count) in instances
+            .into_iter()
+            .flat_map(|instances| &instances.categories)
+        {
+            add_instance_count(&mut self.categories, category, count);
         }
+        add_instance_count(
+            &mut self.vendors,
+            vendor.map(str::to_string),
+            instance_count,
+        );
     }
+}
 
-    fn print_cubase_version_summary(&self) {
-        if self.cubase_version_counts.is_empty() {
-            return;
+/// Determines whether a project was created with a 64-bit version of Cubase from its architecture.
+fn is_64_bit_architecture(architecture: &str) -> bool {
+    matches!(architecture, "WIN64" | "MAC64 LE")
+}
+
+/// Prints the projects which use a plugin being looked up as a table with aligned columns.
+fn print_project_usage_table(project_usage_records: &[ProjectUsageRecord]) {
+    let headings = [
+        "Path",
+        "Cubase Version",
+        "Architecture",
+        "Modified",
+        "Instances",
+    ];
+    let rows = project_usage_records
+        .iter()
+        .map(|record| {
+            [
+                record.path.clone(),
+                record.cubase_version.clone(),
+                record.architecture.clone(),
+                record.modified.clone().unwrap_or_else(|| "-".to_string()),
+                record.instances.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut widths = headings.map(|heading| heading.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    println!("    {}", format_row(&headings.map(String::from)).bold());
+    for row in &rows {
+        println!("    {}", format_row(row));
+    }
+}
+
+/// Prints the plugins which were added to or removed from a project, marking each with a sign.
+fn print_diff_plugins(plugins: &[Plugin], sign: &str, indent: &str) {
+    for plugin in plugins {
+        let plugin_line = format!("{indent}{sign} {} : {}", plugin.guid, plugin.name);
+        if sign == "+" {
+            println!("{}", plugin_line.green());
+        } else {
+            println!("{}", plugin_line.red());
         }
+    }
+}
 
-        let summary_heading = "Summary: Cubase Versions Used In Projects".white().on_red();
+/// Determines whether any plugins were added, removed or renamed between two projects.
+const fn has_plugin_changes(diff: &ProjectDiff) -> bool {
+    !diff.added_plugins.is_empty()
+        || !diff.removed_plugins.is_empty()
+        || !diff.renamed_plugins.is_empty()
+}
 
-        println!();
-        println!("{summary_heading}");
-        println!();
+/// Prints the plugins which were added, removed and renamed between two projects.
+fn print_plugin_changes(diff: &ProjectDiff, indent: &str) {
+    print_diff_plugins(&diff.added_plugins, "+", indent);
+    print_diff_plugins(&diff.removed_plugins, "-", indent);
+    for change in &diff.renamed_plugins {
+        let plugin_line = if change.old.guid == change.new.guid {
+            format!(
+                "{indent}~ {} : {} -> {}",
+                change.old.guid, change.old.name, change.new.name
+            )
+        } else {
+            format!(
+                "{indent}~ {} : {} -> {}",
+                change.old.name, change.old.guid, change.new.guid
+            )
+        };
+        println!("{}", plugin_line.yellow());
+    }
+}
+
+/// Prints a saved version of a project along with its changes from the previous version, or its
+/// plugins when it's the first version.
+fn print_project_version(folder: &Path, version_report: &ProjectVersionReport) {
+    let version_path = Path::new(&version_report.path);
+    let relative_path = version_path.strip_prefix(folder).unwrap_or(version_path);
+    let backup_label = if version_report.backup {
+        format!(" {}", "[backup]".yellow())
+    } else {
+        String::new()
+    };
+    let modified = version_report
+        .modified
+        .as_ref()
+        .map(|modified| format!(", modified {modified}"))
+        .unwrap_or_default();
 
-        let mut sorted_cubase_version_counts = Vec::from_iter(&self.cubase_version_counts);
-        sorted_cubase_version_counts.sort_by(|a, b| natord::compare_ignore_case(a.0, b.0));
+    println!();
+    println!(
+        "{}{backup_label} (Cubase {} ({}){modified})",
+        relative_path.display().to_string().blue(),
+        version_report.cubase_version,
+        version_report.architecture
+    );
 
-        for (cubase_version, count) in &sorted_cubase_version_counts {
-            println!("    > {cubase_version} ({count})");
+    let Some(changes) = &version_report.changes else {
+        for plugin in &version_report.plugins {
+            println!("    > {} : {}", plugin.guid, plugin.name);
         }
+        return;
+    };
+
+    if let Some(version) = &changes.version {
+        let version_line = format!("    ~ Cubase Version: {} -> {}", version.old, version.new);
+        println!("{}", version_line.yellow());
+    }
+    if let Some(architecture) = &changes.architecture {
+        let architecture_line = format!(
+            "    ~ Architecture: {} -> {}",
+            architecture.old, architecture.new
+        );
+        println!("{}", architecture_line.yellow());
+    }
+    print_plugin_changes(changes, "    ");
+    if changes.version.is_none() && changes.architecture.is_none() && !has_plugin_changes(changes) {
+        println!("    > No changes to the Cubase version or plugins");
+    }
+}
+
+/// Prints the differences between two projects.
+fn print_diff(diff: &ProjectDiff) {
+    if diff.is_empty() {
+        println!("{}", "No differences found".green());
+        return;
+    }
+
+    if let Some(version) = &diff.version {
+        println!(
+            "{} {} -> {}",
+            "Cubase Version:".blue(),
+            version.old,
+            version.new
+        );
+    }
+    if let Some(architecture) = &diff.architecture {
+        println!(
+            "{} {} -> {}",
+            "Architecture:".blue(),
+            architecture.old,
+            architecture.new
+        );
+    }
+
+    if has_plugin_changes(diff) {
+        println!();
+        println!("{}", "Plugins:".blue());
+        print_plugin_changes(diff, "    ");
+    }
+
+    if !diff.channels.is_empty() {
+        println!();
+        println!("{}", "Inserts:".blue());
+        for channel_diff in &diff.channels {
+            println!(
+                "    > {} ({})",
+                channel_diff.channel.name, channel_diff.channel.kind
+            );
+            print_diff_plugins(&channel_diff.added, "+", "        ");
+            print_diff_plugins(&channel_diff.removed, "-", "        ");
+        }
+    }
+}
+
+/// Formats a label describing a plugin which isn't installed.
+fn status_label(status: PluginStatus) -> ColoredString {
+    match status {
+        PluginStatus::Installed => "[installed]".green(),
+        PluginStatus::NameOnly => "[matched by name only]".yellow(),
+        PluginStatus::Missing => "[missing]".red(),
+    }
+}
+
+/// Loads the user config from the given path or the default config path, falling back to the
+/// default config when no config file exists.
+fn load_config(config_path: Option<PathBuf>) -> Result<Config> {
+    let config_path = config_path.or_else(|| match cli::default_config_path() {
+        Some(default_config_path) if default_config_path.is_file() => Some(default_config_path),
+        _ => None,
+    });
+
+    let Some(config_path) = config_path else {
+        return Ok(Config::default());
+    };
+
+    let config_string = fs::read_to_string(&config_path).with_context(|| {
+        format!(
+            "unable to open and read config file '{}'",
+            config_path.display().to_string().blue()
+        )
+    })?;
+
+    toml::from_str(&config_string).with_context(|| {
+        format!(
+            "unable to parse config file '{}'",
+            config_path.display().to_string().blue()
+        )
+    })
+}
+
+/// Loads the plugin catalog from the given path or the catalog path in the config, falling back to
+/// the catalog of plugins bundled with Cubase when neither is set.
+fn load_catalog(catalog_path: Option<&Path>, config: &Config) -> Result<Catalog> {
+    catalog_path
+        .or(config.catalog_path.as_deref())
+        .map(Catalog::load)
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+/// Parses the path patterns of project files which should be skipped.
+fn parse_path_ignore_patterns(path_ignore_patterns: &[String]) -> Result<Vec<Pattern>> {
+    path_ignore_patterns
+        .iter()
+        .map(|pattern| {
+            Pattern::new(pattern).with_context(|| {
+                format!("unable to parse path ignore pattern '{}'", pattern.blue())
+            })
+        })
+        .collect()
+}
+
+fn main() {
+    if let Err(error) = run() {
+        print_error(&error, &mut io::stdout());
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    match Cli::parse_command() {
+        Command::Scan(args) => scan(args),
+        Command::Summary(args) => summary(args),
+        Command::WhoUses(args) => who_uses(
+            vec![args.pattern],
+            &args.project_paths,
+            args.options,
+            args.format,
+            args.sort,
+        ),
+        Command::Inspect(args) => inspect(args),
+        Command::Diff(args) => diff(args),
+        Command::History(args) => history(args),
+        Command::ClearCache => clear_cache(),
+        Command::Completions(args) => {
+            print_completions(args.shell);
+            Ok(())
+        }
+    }
+}
+
+/// Prints shell completions for the tool.
+fn print_completions(shell: Shell) {
+    let mut cmd = Cli::command();
+    clap_complete::generate(shell, &mut cmd, env!("CARGO_PKG_NAME"), &mut io::stdout());
+}
+
+/// Removes cached projects from previous scans.
+fn clear_cache() -> Result<()> {
+    cli::default_cache_path().map_or(Ok(()), |cache_path| Cache::clear(&cache_path))
+}
+
+/// Loads the user config along with the plugin catalog and creates a processor which reads
+/// projects using the options shared by each command.
+fn create_processor(
+    options: ProjectOptions,
+    format: Format,
+    filter_patterns: Vec<String>,
+    only_show_filtered: bool,
+) -> Result<Processor> {
+    let mut config = load_config(options.config_path)?;
+    if options.exclude_stock {
+        config.plugins.ignore_stock = true;
+    }
+
+    let catalog = load_catalog(options.catalog.as_deref(), &config)?;
+    let path_ignore_globs = parse_path_ignore_patterns(&config.path_ignore_patterns)?;
+
+    let mut inventory = options
+        .inventory
+        .map(|inventory_path| Inventory::load(&inventory_path))
+        .transpose()?;
+    let inventory_errors = inventory
+        .as_mut()
+        .map(Inventory::take_errors)
+        .unwrap_or_default();
+
+    let thread_pool = ThreadPoolBuilder::new()
+        .num_threads(options.jobs.map_or(0, NonZeroUsize::get))
+        .build()
+        .context("unable to create the thread pool used to read project files")?;
+
+    let mut processor = Processor::new(
+        config,
+        path_ignore_globs,
+        filter_patterns,
+        only_show_filtered,
+        options.inactive,
+        format,
+        thread_pool,
+    )
+    .with_catalog(catalog);
+    if let Some(inventory) = inventory {
+        processor = processor.with_inventory(inventory);
+    }
+    for (module_info_path, error) in inventory_errors {
+        processor.report_path_error(&module_info_path, &error);
+    }
+    if !options.categories.is_empty() {
+        processor = processor.with_categories(options.categories.into_iter().map(Into::into));
+    }
+    if !options.no_cache
+        && let Some(cache_path) = cli::default_cache_path()
+    {
+        processor = processor.with_cache(Cache::load(&cache_path));
+    }
+
+    Ok(processor)
+}
+
+/// Shows the plugins used by each project followed by summaries across all projects.
+fn scan(args: ScanArgs) -> Result<()> {
+    // Display shell completions.
+    if let Some(shell) = args.completions {
+        print_completions(shell);
+        return Ok(());
+    }
+
+    // Remove cached projects from previous scans.
+    if args.clear_cache {
+        return clear_cache();
+    }
+
+    if !args.who_uses.is_empty() {
+        return who_uses(
+            args.who_uses,
+            &args.project_paths,
+            args.options,
+            args.format,
+            args.sort,
+        );
+    }
+
+    let format = args.format;
+    if args.watch && format != Format::Text {
+        bail!("watch mode is only supported with the text output format");
+    }
+
+    // Process Cubase project files.
+    let mut processor =
+        create_processor(args.options, format, args.patterns, args.only_show_filtered)?
+            .with_state_options(args.states);
+    if args.media {
+        processor = processor.with_media();
+    }
+    if args.tracks {
+        processor = processor.with_tracks();
+    }
+    if args.backups {
+        processor = processor.with_backups();
+    }
+    if args.count_backups {
+        processor = processor.with_backup_summaries();
+    }
+
+    let watch_targets = args
+        .watch
+        .then(|| processor.watch_targets(&args.project_paths))
+        .transpose()?;
+
+    processor.process_cubase_project_paths(&args.project_paths);
+
+    if let Err(error) = processor.save_cache() {
+        processor.report_error(&error);
+    }
+
+    match format {
+        Format::Text => processor.print_summaries(),
+        Format::Json => processor.print_json()?,
+        Format::Csv => processor.print_csv()?,
+    }
+
+    if let Some(summary_csv_path) = &args.summary_csv {
+        processor.write_summary_csv(summary_csv_path)?;
+    }
+
+    if let Some(watch_targets) = watch_targets {
+        processor.watch(&watch_targets, args.summary_csv.as_deref())?;
+    }
+
+    Ok(())
+}
+
+/// Shows only the summaries of plugins and Cubase versions used across all projects.
+fn summary(args: SummaryArgs) -> Result<()> {
+    let format = args.format;
+    let only_show_filtered = !args.patterns.is_empty();
+    let mut processor = create_processor(args.options, format, args.patterns, only_show_filtered)?
+        .with_summaries_only();
+
+    processor.process_cubase_project_paths(&args.project_paths);
+
+    if let Err(error) = processor.save_cache() {
+        processor.report_error(&error);
+    }
+
+    match format {
+        Format::Text => processor.print_summaries(),
+        Format::Json => processor.print_json()?,
+        Format::Csv => processor.print_summary_csv()?,
+    }
+
+    if let Some(summary_csv_path) = &args.summary_csv {
+        processor.write_summary_csv(summary_csv_path)?;
+    }
+
+    Ok(())
+}
+
+/// Lists the projects which use a plugin matching any of the patterns as a table.
+fn who_uses(
+    patterns: Vec<String>,
+    project_paths: &[String],
+    options: ProjectOptions,
+    format: Format,
+    sort_order: SortOrder,
+) -> Result<()> {
+    let mut processor =
+        create_processor(options, format, patterns, false)?.with_who_uses(sort_order);
+
+    processor.process_cubase_project_paths(project_paths);
+
+    if let Err(error) = processor.save_cache() {
+        processor.report_error(&error);
+    }
+
+    processor.print_project_usage()
+}
+
+/// Shows everything about a single project, including its media files and tracks.
+fn inspect(args: InspectArgs) -> Result<()> {
+    let format = args.format;
+    let mut processor = create_processor(args.options, format, Vec::new(), false)?
+        .with_state_options(args.states)
+        .with_media()
+        .with_tracks();
+
+    let project =
+        read_cubase_project_file(&args.project_file_path, processor.state_encoding.is_some())
+            .with_context(|| {
+                format!(
+                    "unable to inspect project file '{}'",
+                    args.project_file_path.display().to_string().blue()
+                )
+            })?;
+    processor.process_cubase_project(&args.project_file_path, project);
+
+    match format {
+        Format::Text => {}
+        Format::Json => processor.print_json()?,
+        Format::Csv => processor.print_csv()?,
+    }
+
+    Ok(())
+}
+
+/// Compares the Cubase version, architecture and plugins of two projects, excluding the plugins
+/// which are ignored by the config.
+fn diff(args: DiffArgs) -> Result<()> {
+    let processor = create_processor(args.options, args.format.into(), Vec::new(), false)?;
+
+    let read_project = |project_file_path: &Path| {
+        read_cubase_project_file(project_file_path, false)
+            .map(|project| processor.filter_project(project))
+            .with_context(|| {
+                format!(
+                    "unable to compare project file '{}'",
+                    project_file_path.display().to_string().blue()
+                )
+            })
+    };
+    let old_project = read_project(&args.old_project_file_path)?;
+    let new_project = read_project(&args.new_project_file_path)?;
+    let diff = ProjectDiff::new(&old_project, &new_project);
+
+    if args.format == DiffFormat::Json {
+        let report = DiffReport {
+            old_path: args.old_project_file_path.display().to_string(),
+            new_path: args.new_project_file_path.display().to_string(),
+            diff: &diff,
+        };
+
+        let mut stdout = io::stdout().lock();
+        serde_json::to_writer_pretty(&mut stdout, &report)
+            .context("unable to write the JSON report")?;
+        writeln!(stdout).context("unable to write the JSON report")?;
+        return Ok(());
+    }
+
+    let diff_heading = format!(
+        "Diff: {} -> {}",
+        args.old_project_file_path.display(),
+        args.new_project_file_path.display()
+    )
+    .white()
+    .on_red();
+    println!();
+    println!("{diff_heading}");
+    println!();
+    print_diff(&diff);
+
+    Ok(())
+}
+
+/// Shows how the plugins and Cubase version of each project evolved across its saved versions.
+fn history(args: HistoryArgs) -> Result<()> {
+    let mut processor = create_processor(args.options, args.format.into(), Vec::new(), false)?
+        .with_history(args.sort);
+    processor.process_cubase_project_paths(&args.project_paths);
+    processor.print_history()
+}
+
+/// A saved version of a project which was read by the history command.
+struct SavedVersion {
+    /// Path of the Cubase project file.
+    path: PathBuf,
+    /// Version of the project identified from the name of its file.
+    version: ProjectVersion,
+    /// Time the project file was last modified, if it could be determined.
+    modified: Option<SystemTime>,
+    /// The project excluding any ignored plugins.
+    project: Project,
+}
+
+/// Describes a saved version of a project along with its changes from the previous version.
+fn project_version_report(
+    saved_version: &SavedVersion,
+    previous_project: Option<&Project>,
+) -> ProjectVersionReport {
+    ProjectVersionReport {
+        path: saved_version.path.display().to_string(),
+        number: saved_version.version.number,
+        backup: saved_version.version.backup,
+        cubase_version: saved_version.project.metadata.version.clone(),
+        architecture: saved_version.project.metadata.architecture.clone(),
+        modified: saved_version
+            .modified
+            .map(|modified| humantime::format_rfc3339_seconds(modified).to_string()),
+        plugins: sort_plugins(saved_version.project.plugins.iter().cloned()),
+        changes: previous_project
+            .map(|previous_project| ProjectDiff::new(previous_project, &saved_version.project)),
+    }
+}
+
+/// What is done with each project which is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Show each project and count it towards the summaries.
+    Projects,
+    /// Only count each project towards the summaries.
+    Summaries,
+    /// Record each project which uses a plugin being looked up, listing them in the given order.
+    WhoUses(SortOrder),
+    /// Record each saved version of each project, listing them in the given order.
+    History(VersionOrder),
+}
+
+struct Processor {
+    config: Config,
+    path_ignore_globs: Vec<Pattern>,
+    filter_patterns: Vec<WildMatchPattern<'*', '?'>>,
+    thread_pool: ThreadPool,
+    cache: Option<Cache>,
+    inventory: Option<Inventory>,
+    catalog: Catalog,
+    categories: HashSet<PluginCategory>,
+    state_encoding: Option<StateEncoding>,
+    preset_directories: Option<PresetDirectories>,
+    mode: Mode,
+    project_file_kinds: Vec<ProjectFileKind>,
+    summary_file_kinds: Vec<ProjectFileKind>,
+    list_media: bool,
+    list_tracks: bool,
+    plugin_counts_32: HashMap<Plugin, PluginUsage>,
+    plugin_counts_64: HashMap<Plugin, PluginUsage>,
+    plugin_counts: HashMap<Plugin, PluginUsage>,
+    missing_plugin_counts: HashMap<Plugin, PluginUsage>,
+    category_counts: HashMap<PluginCategory, PluginUsage>,
+    vendor_counts: HashMap<Option<String>, PluginUsage>,
+    cubase_version_counts: HashMap<String, i32>,
+    sample_rate_counts: HashMap<String, i32>,
+    project_counts: HashMap<PathBuf, ProjectCounts>,
+    only_show_filtered: bool,
+    inactive: Inactive,
+    format: Format,
+    project_reports: Vec<ProjectReport>,
+    project_usage_records: Vec<ProjectUsageRecord>,
+    saved_versions: Vec<SavedVersion>,
+    error_reports: Vec<ErrorReport>,
+    entry_modified_times: HashMap<PathBuf, SystemTime>,
+}
+
+impl Processor {
+    pub fn new(
+        config: Config,
+        path_ignore_globs: impl IntoIterator<Item = Pattern>,
+        filter_patterns: impl IntoIterator<Item = String>,
+        only_show_filtered: bool,
+        inactive: Inactive,
+        format: Format,
+        thread_pool: ThreadPool,
+    ) -> Self {
+        Self {
+            config,
+            path_ignore_globs: path_ignore_globs.into_iter().collect(),
+            filter_patterns: filter_patterns
+                .into_iter()
+                .map(|pattern| WildMatch::new_case_insensitive(&pattern))
+                .collect::<Vec<_>>(),
+            thread_pool,
+            cache: None,
+            inventory: None,
+            catalog: Catalog::default(),
+            categories: HashSet::new(),
+            state_encoding: None,
+            preset_directories: None,
+            mode: Mode::Projects,
+            project_file_kinds: vec![ProjectFileKind::Project],
+            summary_file_kinds: vec![ProjectFileKind::Project],
+            list_media: false,
+            list_tracks: false,
+            plugin_counts_32: HashMap::new(),
+            plugin_counts_64: HashMap::new(),
+            plugin_counts: HashMap::new(),
+            missing_plugin_counts: HashMap::new(),
+            category_counts: HashMap::new(),
+            vendor_counts: HashMap::new(),
+            cubase_version_counts: HashMap::new(),
+            sample_rate_counts: HashMap::new(),
+            project_counts: HashMap::new(),
+            only_show_filtered,
+            inactive,
+            format,
+            project_reports: Vec::new(),
+            project_usage_records: Vec::new(),
+            saved_versions: Vec::new(),
+            error_reports: Vec::new(),
+            entry_modified_times: HashMap::new(),
+        }
+    }
+
+    /// Classifies each plugin as installed or missing using an inventory of installed plugins.
+    pub fn with_inventory(mut self, inventory: Inventory) -> Self {
+        self.inventory = Some(inventory);
+        self
+    }
+
+    /// Looks up the vendor of each plugin and whether it's bundled with Cubase using a catalog.
+    pub fn with_catalog(mut self, catalog: Catalog) -> Self {
+        self.catalog = catalog;
+        self
+    }
+
+    /// Only includes plugin instances within the given categories.
+    pub fn with_categories(mut self, categories: impl IntoIterator<Item = PluginCategory>) -> Self {
+        self.categories = categories.into_iter().collect();
+        self
+    }
+
+    /// Reads the saved state of each plugin instance, encoding it in the given format within JSON
+    /// output.
+    pub const fn with_plugin_states(mut self, state_encoding: StateEncoding) -> Self {
+        self.state_encoding = Some(state_encoding);
+        self
+    }
+
+    /// Writes the saved state of each plugin instance to a preset file within the given directory.
+    pub fn with_preset_export(mut self, preset_directory: PathBuf) -> Self {
+        self.preset_directories = Some(PresetDirectories::new(preset_directory));
+        self
+    }
+
+    /// Lists the projects which use a plugin matching the filter patterns in the given order rather
+    /// than showing each project.
+    pub const fn with_who_uses(mut self, sort_order: SortOrder) -> Self {
+        self.mode = Mode::WhoUses(sort_order);
+        self
+    }
+
+    /// Shows how each project evolved across its saved versions in the given order, including the
+    /// backups which Cubase writes when projects are saved.
+    pub fn with_history(mut self, version_order: VersionOrder) -> Self {
+        self.mode = Mode::History(version_order);
+        self.project_file_kinds.push(ProjectFileKind::Backup);
+        self
+    }
+
+    /// Also reads the backup and auto-save files which Cubase writes, without counting them
+    /// towards the summaries.
+    pub fn with_backups(mut self) -> Self {
+        self.project_file_kinds
+            .extend([ProjectFileKind::Backup, ProjectFileKind::AutoSave]);
+        self
+    }
+
+    /// Counts backup and auto-save files towards the summaries.
+    pub fn with_backup_summaries(mut self) -> Self {
+        self.summary_file_kinds
+            .extend([ProjectFileKind::Backup, ProjectFileKind::AutoSave]);
+        self
+    }
+
+    /// Only counts each project towards the summaries rather than showing it.
+    pub const fn with_summaries_only(mut self) -> Self {
+        self.mode = Mode::Summaries;
+        self
+    }
+
+    /// Reads the saved state of each plugin instance and exports it to preset files as requested
+    /// by the state options.
+    pub fn with_state_options(mut self, state_options: StateOptions) -> Self {
+        if state_options.states || state_options.export_presets.is_some() {
+            self = self.with_plugin_states(state_options.state_encoding);
+        }
+        if let Some(preset_directory) = state_options.export_presets {
+            self = self.with_preset_export(preset_directory);
+        }
+        self
+    }
+
+    /// Lists the media files referenced by each project.
+    pub const fn with_media(mut self) -> Self {
+        self.list_media = true;
+        self
+    }
+
+    /// Lists the tracks of each project.
+    pub const fn with_tracks(mut self) -> Self {
+        self.list_tracks = true;
+        self
+    }
+
+    /// Uses cached projects from previous scans for project files which haven't changed.
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Writes any newly parsed projects to the cache.
+    pub fn save_cache(&mut self) -> Result<()> {
+        self.cache.as_mut().map_or(Ok(()), Cache::save)
+    }
+
+    fn plugin_status(&self, plugin: &Plugin) -> Option<PluginStatus> {
+        self.inventory
+            .as_ref()
+            .map(|inventory| inventory.status(&plugin.guid, &plugin.name))
+    }
+
+    /// Reports an error which isn't related to a particular project, printing it to stderr unless
+    /// the output is text so that it doesn't corrupt JSON or CSV output.
+    pub fn report_error(&self, error: &Error) {
+        match self.format {
+            Format::Text => print_error(error, &mut io::stdout()),
+            Format::Json | Format::Csv => print_error(error, &mut io::stderr()),
+        }
+    }
+
+    /// Reports an error related to a project path given by the user, either by printing it below
+    /// a heading containing the path or by recording it in the JSON report. Errors are printed
+    /// to stderr along with their causes in CSV mode so that stdout only contains CSV records.
+    pub fn report_path_error(&mut self, project_path: &Path, error: &Error) {
+        match self.format {
+            Format::Text => {
+                print_path_heading(project_path);
+                print_error(error, &mut io::stdout());
+            }
+            Format::Json => self
+                .error_reports
+                .push(ErrorReport::new(project_path, error)),
+            Format::Csv => print_path_error(project_path, error),
+        }
+    }
+
+    /// Reports an error related to a single project file, either by printing it or by recording it
+    /// in the JSON report.
+    fn report_file_error(&mut self, project_file_path: &Path, error: &Error) {
+        match self.format {
+            Format::Text => print_error(error, &mut io::stdout()),
+            Format::Json => self
+                .error_reports
+                .push(ErrorReport::new(project_file_path, error)),
+            Format::Csv => print_path_error(project_file_path, error),
+        }
+    }
+
+    /// Determines the time a project file was last modified, using the time stored in the archive
+    /// for project files which were read from archives.
+    fn modified_time(&self, project_file_path: &Path) -> Option<SystemTime> {
+        self.entry_modified_times
+            .get(project_file_path)
+            .copied()
+            .or_else(|| {
+                fs::metadata(project_file_path)
+                    .and_then(|file_metadata| file_metadata.modified())
+                    .ok()
+            })
+    }
+
+    /// Determines whether a project file is excluded by the path ignore patterns.
+    ///
+    /// When showing the history of projects, the patterns are matched against the path which each
+    /// file would have within the folder of its project, so that patterns which exclude older
+    /// versions and auto-saves (e.g. `**/Older Versions*/**/*.cpr`) don't exclude the versions
+    /// which the history consists of.
+    fn is_path_ignored(&self, project_file_path: &Path) -> bool {
+        let project_file_path = match (&self.mode, project_file_path.file_name()) {
+            (Mode::History(_), Some(file_name)) => {
+                Cow::Owned(history::project_folder(project_file_path).join(file_name))
+            }
+            _ => Cow::Borrowed(project_file_path),
+        };
+        self.path_ignore_globs
+            .iter()
+            .any(|glob| glob.matches_path(&project_file_path))
+    }
+
+    /// Determines the extensions of the project files which are read.
+    fn project_file_extensions(&self) -> Vec<&'static str> {
+        self.project_file_kinds
+            .iter()
+            .map(|kind| kind.extension())
+            .collect()
+    }
+
+    /// Finds all project files and zip or tar archives within a project path which aren't ignored.
+    fn find_cubase_project_files(&self, project_path: &Path) -> Result<Vec<GlobResult>> {
+        let mut project_file_paths = Vec::new();
+        for extension in self
+            .project_file_extensions()
+            .into_iter()
+            .chain(bundle::BUNDLE_EXTENSIONS.iter().copied())
+        {
+            let project_file_path_pattern = project_path.join("**").join(format!("*.{extension}"));
+            let Some(project_file_path_pattern) = project_file_path_pattern.to_str() else {
+                bail!("unable to convert the project file pattern to a string");
+            };
+
+            project_file_paths.extend(
+                glob::glob_with(
+                    project_file_path_pattern,
+                    MatchOptions {
+                        case_sensitive: false,
+                        require_literal_separator: false,
+                        require_literal_leading_dot: false,
+                    },
+                )
+                .context("unable to glob for project files in the project path")?
+                .filter(|project_file_path| {
+                    project_file_path
+                        .as_ref()
+                        .map_or(true, |project_file_path| {
+                            !self.is_path_ignored(project_file_path)
+                        })
+                }),
+            );
+        }
+
+        Ok(project_file_paths)
+    }
+
+    /// Processes the project files within each project path, which may be a directory, a project
+    /// file, a zip or tar archive, a glob pattern or `-` to read from standard input, reporting
+    /// paths which don't exist or couldn't be searched.
+    ///
+    /// The project files within all project paths are found before any are read, so that those
+    /// found through several overlapping project paths are only processed once.
+    pub fn process_cubase_project_paths(&mut self, project_paths: &[String]) {
+        let mut project_files = ProjectFileSet::default();
+        for project_path in project_paths {
+            if project_path == STDIN_PATH {
+                let stdin_bytes = read_stdin();
+                if let Err(error) =
+                    stdin_bytes.and_then(|bytes| self.process_stdin(bytes, &mut project_files))
+                {
+                    self.report_path_error(Path::new(STDIN_PATH), &error);
+                }
+                continue;
+            }
+
+            self.find_project_path_argument(project_path, &mut project_files);
+        }
+
+        self.process_project_files(&project_files.sources);
+    }
+
+    /// Queues a single project read from standard input to be processed along with the project
+    /// files, or finds the project files within each of a newline-separated list of project paths
+    /// read from standard input.
+    fn process_stdin(
+        &mut self,
+        stdin_bytes: Vec<u8>,
+        project_files: &mut ProjectFileSet,
+    ) -> Result<()> {
+        if PROJECT_HEADERS
+            .iter()
+            .any(|header| stdin_bytes.starts_with(header))
+        {
+            project_files.insert_stdin(stdin_bytes);
+            return Ok(());
+        }
+
+        let project_paths = String::from_utf8(stdin_bytes)
+            .context("standard input is neither a project nor a list of project paths")?;
+        for project_path in project_paths
+            .lines()
+            .filter(|project_path| !project_path.trim().is_empty())
+        {
+            self.find_project_path_argument(project_path, project_files);
+        }
+        Ok(())
+    }
+
+    /// Finds the project files within a project path given as an argument or read from standard
+    /// input, expanding it as a glob pattern when no such path exists.
+    fn find_project_path_argument(
+        &mut self,
+        project_path: &str,
+        project_files: &mut ProjectFileSet,
+    ) {
+        let path = Path::new(project_path);
+        if path.exists() {
+            self.find_project_path(path, project_files);
+            return;
+        }
+
+        if !is_glob_pattern(project_path) {
+            self.report_path_error(path, &anyhow!("the path does not exist"));
+            return;
+        }
+
+        match self.find_glob_matches(project_path) {
+            Ok(matched_paths) => {
+                for matched_path in matched_paths {
+                    self.find_project_path(&matched_path, project_files);
+                }
+            }
+            Err(error) => self.report_path_error(path, &error),
+        }
+    }
+
+    /// Finds the directories, project files and zip or tar archives matching a glob pattern.
+    fn find_glob_matches(&self, pattern: &str) -> Result<Vec<PathBuf>> {
+        let extensions = self.project_file_extensions();
+        let is_project_file = |path: &Path| {
+            path.extension().is_some_and(|extension| {
+                extensions.iter().any(|project_file_extension| {
+                    extension.eq_ignore_ascii_case(project_file_extension)
+                })
+            })
+        };
+
+        let mut matched_paths = Vec::new();
+        for matched_path in glob::glob(pattern).context("unable to parse the glob pattern")? {
+            let matched_path =
+                matched_path.context("unable to glob a particular path matching the pattern")?;
+            if matched_path.is_dir()
+                || bundle::is_bundle(&matched_path)
+                || is_project_file(&matched_path)
+            {
+                matched_paths.push(matched_path);
+            }
+        }
+
+        if matched_paths.is_empty() {
+            bail!("the pattern didn't match any directories, project files or archives");
+        }
+        Ok(matched_paths)
+    }
+
+    /// Finds the project file or archive at a path, or those within a directory.
+    fn find_project_path(&mut self, project_path: &Path, project_files: &mut ProjectFileSet) {
+        if project_path.is_file() {
+            project_files.insert(project_path.to_path_buf());
+            return;
+        }
+
+        let project_file_paths = match self.find_cubase_project_files(project_path) {
+            Ok(project_file_paths) => project_file_paths,
+            Err(error) => {
+                self.report_path_error(project_path, &error);
+                return;
+            }
+        };
+        for project_file_path in project_file_paths {
+            match project_file_path {
+                Ok(project_file_path) => project_files.insert(project_file_path),
+                Err(error) => {
+                    let error_path = error.path().to_path_buf();
+                    let error = Error::from(error)
+                        .context("unable to glob a particular project file in the project path");
+                    self.report_file_error(&error_path, &error);
+                }
+            }
+        }
+    }
+
+    /// Reads and processes project files and archives, along with any project read from standard
+    /// input.
+    ///
+    /// Project files are read and parsed concurrently in batches, but the results of each batch
+    /// are processed in the original order so that output and summaries remain deterministic.
+    fn process_project_files(&mut self, project_sources: &[ProjectSource]) {
+        for batch in project_sources.chunks(PROJECT_BATCH_SIZE) {
+            let cache = self.cache.as_ref();
+            let read_states = self.state_encoding.is_some();
+            let extensions = self.project_file_extensions();
+            let results = self.thread_pool.install(|| {
+                batch
+                    .par_iter()
+                    .map(|project_source| {
+                        let project_file_path = match project_source {
+                            ProjectSource::Path(project_file_path) => project_file_path,
+                            ProjectSource::Stdin(project_bytes) => {
+                                return vec![ReadProject {
+                                    path: PathBuf::from(STDIN_PROJECT_PATH),
+                                    project: parse_cubase_project(project_bytes, read_states),
+                                    stamp: None,
+                                    entry_modified: None,
+                                }];
+                            }
+                        };
+                        if bundle::is_bundle(project_file_path) {
+                            return read_bundle_projects(
+                                project_file_path,
+                                &extensions,
+                                read_states,
+                            );
+                        }
+
+                        let (project, stamp) =
+                            read_cubase_project_file_cached(project_file_path, cache, read_states);
+                        vec![ReadProject {
+                            path: project_file_path.clone(),
+                            project,
+                            stamp,
+                            entry_modified: None,
+                        }]
+                    })
+                    .collect::<Vec<_>>()
+            });
+
+            for projects in results {
+                self.process_read_projects(projects);
+            }
+        }
+    }
+
+    /// Processes projects which were read from a project file or archive, caching those which were
+    /// read from project files and skipping archived project files which are ignored.
+    fn process_read_projects(&mut self, projects: Vec<ReadProject>) {
+        for ReadProject {
+            path: project_file_path,
+            project,
+            stamp,
+            entry_modified,
+        } in projects
+        {
+            if self.is_path_ignored(&project_file_path) {
+                continue;
+            }
+            if let Some(modified) = entry_modified {
+                self.entry_modified_times
+                    .insert(project_file_path.clone(), modified);
+            }
+
+            match project {
+                Ok(project) => {
+                    if let (Some(cache), Some(stamp)) = (&mut self.cache, stamp) {
+                        cache.insert(&project_file_path, stamp, project.clone());
+                    }
+                    self.process_cubase_project(&project_file_path, project);
+                }
+                Err(error) => self.report_file_error(&project_file_path, &error),
+            }
+        }
+    }
+
+    /// Reads and processes a single project file, replacing the counts from any previous scan of
+    /// the file.
+    fn process_cubase_project_file(&mut self, project_file_path: &Path) {
+        self.uncount_project(project_file_path);
+
+        match read_cubase_project_file(project_file_path, self.state_encoding.is_some()) {
+            Ok(project) => self.process_cubase_project(project_file_path, project),
+            Err(error) => self.report_path_error(project_file_path, &error),
+        }
+    }
+
+    /// Determines the directories and project files to watch from the project paths, including
+    /// those matching glob patterns. Archives and standard input can't be watched.
+    pub fn watch_targets(&self, project_paths: &[String]) -> Result<WatchTargets> {
+        let mut watch_targets = WatchTargets::default();
+        for project_path in project_paths {
+            let path = Path::new(project_path);
+            let matched_paths = if path.exists() {
+                vec![path.to_path_buf()]
+            } else if is_glob_pattern(project_path) {
+                self.find_glob_matches(project_path).unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+
+            for matched_path in matched_paths
+                .iter()
+                .filter(|matched_path| !bundle::is_bundle(matched_path))
+            {
+                watch_targets.insert(matched_path);
+            }
+        }
+
+        if watch_targets.is_empty() {
+            bail!("watch mode requires at least one directory or project file to watch");
+        }
+        Ok(watch_targets)
+    }
+
+    /// Watches directories for project files which are added, modified or removed along with
+    /// individual project files, and rescans them before displaying the updated summaries.
+    pub fn watch(
+        &mut self,
+        watch_targets: &WatchTargets,
+        summary_csv_path: Option<&Path>,
+    ) -> Result<()> {
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(sender).context("unable to create the file watcher")?;
+        let watch_paths = watch_targets
+            .directories
+            .iter()
+            .map(|(directory, _)| (directory.as_path(), RecursiveMode::Recursive))
+            .chain(
+                watch_targets
+                    .project_file_directories()
+                    .into_iter()
+                    .map(|directory| (directory, RecursiveMode::NonRecursive)),
+            );
+        for (watch_path, recursive_mode) in watch_paths {
+            watcher.watch(watch_path, recursive_mode).with_context(|| {
+                format!(
+                    "unable to watch project path '{}'",
+                    watch_path.display().to_string().blue()
+                )
+            })?;
+        }
+
+        println!();
+        println!("{}", "Watching for changes to projects...".blue());
+
+        while let Ok(event) = receiver.recv() {
+            // Saving a project usually produces several events in quick succession, so they are
+            // gathered together before the affected projects are rescanned.
+            let mut changed_paths = BTreeSet::new();
+            let mut event = Some(event);
+            while let Some(result) = event {
+                match result {
+                    Ok(event) => {
+                        changed_paths
+                            .extend(self.changed_project_file_paths(&event, watch_targets));
+                    }
+                    Err(error) => {
+                        print_error(
+                            &Error::from(error).context("unable to watch for changes"),
+                            &mut io::stdout(),
+                        );
+                    }
+                }
+                event = receiver.recv_timeout(WATCH_DEBOUNCE_DURATION).ok();
+            }
+
+            if changed_paths.is_empty() {
+                continue;
+            }
+
+            for project_file_path in changed_paths {
+                if project_file_path.is_file() {
+                    self.process_cubase_project_file(&project_file_path);
+                } else if self.project_counts.contains_key(&project_file_path) {
+                    self.uncount_project(&project_file_path);
+                    let project_file_path_heading =
+                        format!("Removed: {}", project_file_path.display())
+                            .white()
+                            .on_red();
+                    println!();
+                    println!("{project_file_path_heading}");
+                }
+            }
+
+            self.print_summaries();
+            if let Some(summary_csv_path) = summary_csv_path {
+                self.write_summary_csv(summary_csv_path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Determines which project files may have been added, modified or removed due to a file
+    /// system event, using the paths which they were scanned with.
+    fn changed_project_file_paths(
+        &self,
+        event: &Event,
+        watch_targets: &WatchTargets,
+    ) -> Vec<PathBuf> {
+        if event.kind.is_access() {
+            return Vec::new();
+        }
+
+        let project_file_extensions = self.project_file_extensions();
+        let is_project_file = |path: &Path| {
+            path.extension().is_some_and(|extension| {
+                project_file_extensions
+                    .iter()
+                    .any(|project_file_extension| {
+                        extension.eq_ignore_ascii_case(project_file_extension)
+                    })
+            })
+        };
+
+        let mut changed_paths = Vec::new();
+        for path in event
+            .paths
+            .iter()
+            .filter_map(|path| watch_targets.resolve(path))
+        {
+            let path = path.as_path();
+            if is_project_file(path) {
+                if !self.is_path_ignored(path) {
+                    changed_paths.push(path.to_path_buf());
+                }
+                continue;
+            }
+
+            // Directories which are created, moved or removed affect all the projects within them,
+            // while other changes to directories are caused by changes to their contents.
+            if !matches!(
+                event.kind,
+                EventKind::Create(_)
+                    | EventKind::Remove(_)
+                    | EventKind::Modify(ModifyKind::Name(_))
+            ) {
+                continue;
+            }
+
+            changed_paths.extend(
+                self.project_counts
+                    .keys()
+                    .filter(|project_file_path| project_file_path.starts_with(path))
+                    .cloned(),
+            );
+            // Archives aren't rescanned since they are rarely modified.
+            if path.is_dir()
+                && let Ok(project_file_paths) = self.find_cubase_project_files(path)
+            {
+                changed_paths.extend(
+                    project_file_paths
+                        .into_iter()
+                        .flatten()
+                        .filter(|project_file_path| !bundle::is_bundle(project_file_path)),
+                );
+            }
+        }
+        changed_paths
+    }
+
+    /// Determines whether a plugin is excluded from output by the config, either explicitly or
+    /// because it's bundled with Cubase.
+    fn is_ignored(&self, plugin: &Plugin) -> bool {
+        self.config.plugins.guid_ignores.contains(&plugin.guid)
+            || self.config.plugins.name_ignores.contains(&plugin.name)
+            || self.config.plugins.ignore_stock && self.catalog.is_stock(&plugin.guid)
+    }
+
+    fn matches_filters(&self, plugin: &Plugin) -> bool {
+        self.filter_patterns.is_empty()
+            || self
+                .filter_patterns
+                .iter()
+                .any(|pattern| pattern.matches(&plugin.name) || pattern.matches(&plugin.guid))
+    }
+
+    /// Removes instances which are inactive or outside the selected categories when they are
+    /// excluded, along with plugins which are only used by such instances.
+    fn exclude_instances(
+        &self,
+        plugins: &mut HashSet<Plugin>,
+        instances: &mut Vec<PluginInstance>,
+    ) {
+        if self.inactive == Inactive::Exclude {
+            retain_instances(plugins, instances, |instance| !instance.is_inactive());
+        }
+        if !self.categories.is_empty() {
+            retain_instances(plugins, instances, |instance| {
+                self.categories.contains(&instance.category)
+            });
+        }
+    }
+
+    /// Removes ignored plugins from a project along with their instances, as well as instances
+    /// which are inactive or outside the selected categories when they are excluded.
+    fn filter_project(&self, mut project: Project) -> Project {
+        self.exclude_instances(&mut project.plugins, &mut project.instances);
+        project.plugins.retain(|plugin| !self.is_ignored(plugin));
+        project
+            .instances
+            .retain(|instance| project.plugins.contains(&instance.plugin));
+        project
+    }
+
+    fn process_cubase_project(&mut self, project_file_path: &Path, project_details: Project) {
+        let Project {
+            metadata,
+            mut plugins,
+            mut instances,
+            media,
+            tracks,
+        } = project_details;
+
+        self.exclude_instances(&mut plugins, &mut instances);
+
+        let plugin_instances = group_instances(&instances);
+
+        let sorted_plugins = sort_plugins(plugins);
+        let filtered_plugins = sorted_plugins
+            .iter()
+            .filter(|plugin| !self.is_ignored(plugin))
+            .collect::<Vec<_>>();
+
+        if !self.filter_patterns.is_empty()
+            && !filtered_plugins
+                .iter()
+                .any(|plugin| self.matches_filters(plugin))
+        {
+            return;
+        }
+
+        if matches!(self.mode, Mode::WhoUses(_)) {
+            self.record_project_usage(
+                project_file_path,
+                &metadata,
+                &filtered_plugins,
+                &plugin_instances,
+            );
+            return;
+        }
+
+        if matches!(self.mode, Mode::History(_)) {
+            self.record_saved_version(project_file_path, metadata, &filtered_plugins, instances);
+            return;
+        }
+
+        // Projects are only shown as text or included in reports when they aren't solely counted
+        // towards the summaries.
+        let is_text = self.format == Format::Text && self.mode == Mode::Projects;
+
+        if is_text {
+            print_path_heading(project_file_path);
+        }
+
+        let is_64_bit = is_64_bit_architecture(&metadata.architecture);
+        if !self.reports_architecture(is_64_bit) {
+            return;
+        }
+
+        let cubase_version = format!(
+            "{application} {version} ({architecture})",
+            application = metadata.application,
+            version = metadata.version,
+            architecture = metadata.architecture
+        );
+
+        let sample_rate = metadata
+            .sample_rate
+            .map_or_else(|| "Unspecified".to_string(), format_sample_rate);
+
+        let preset_paths =
+            self.export_presets(project_file_path, &filtered_plugins, &plugin_instances);
+
+        if is_text {
+            self.print_project_heading(
+                project_file_path,
+                &cubase_version,
+                &metadata,
+                &media,
+                &tracks,
+            );
+        } else if self.mode == Mode::Projects {
+            self.project_reports.push(ProjectReport {
+                path: project_file_path.display().to_string(),
+                file_kind: ProjectFileKind::from_path(project_file_path),
+                metadata,
+                plugins: self.plugin_reports(&filtered_plugins, &plugin_instances, &preset_paths),
+                media: self
+                    .list_media
+                    .then(|| media_reports(project_file_path, &media)),
+                tracks: self.list_tracks.then_some(tracks),
+            });
+        }
+
+        if filtered_plugins.is_empty() {
+            return;
+        }
+
+        if is_text {
+            println!();
+        }
+
+        let mut project_counts = ProjectCounts {
+            cubase_version,
+            sample_rate,
+            is_64_bit,
+            plugins: Vec::new(),
+            categories: Vec::new(),
+            vendors: Vec::new(),
+        };
+
+        for plugin in filtered_plugins {
+            let instances = plugin_instances.get(plugin);
+            project_counts.add_plugin(plugin, instances, self.catalog.vendor(&plugin.guid));
+
+            if is_text && (!self.only_show_filtered || self.matches_filters(plugin)) {
+                self.print_plugin(plugin, instances);
+            }
+        }
+
+        self.count_project(project_file_path, project_counts);
+    }
+
+    /// Determines whether projects created with a 32-bit or 64-bit version of Cubase are reported.
+    const fn reports_architecture(&self, is_64_bit: bool) -> bool {
+        if is_64_bit {
+            self.config.projects.report_64_bit
+        } else {
+            self.config.projects.report_32_bit
+        }
+    }
+
+    /// Records a project which uses a plugin being looked up along with the number of instances of
+    /// matching plugins.
+    fn record_project_usage(
+        &mut self,
+        project_file_path: &Path,
+        metadata: &Metadata,
+        plugins: &[&Plugin],
+        plugin_instances: &HashMap<&Plugin, PluginInstances<'_>>,
+    ) {
+        if !self.reports_architecture(is_64_bit_architecture(&metadata.architecture)) {
+            return;
+        }
+
+        let instances = plugins
+            .iter()
+            .filter(|plugin| self.matches_filters(plugin))
+            .filter_map(|plugin| plugin_instances.get(plugin))
+            .map(|instances| instances.count)
+            .sum();
+        let modified = self
+            .modified_time(project_file_path)
+            .map(|modified| humantime::format_rfc3339_seconds(modified).to_string());
+
+        self.project_usage_records.push(ProjectUsageRecord {
+            path: project_file_path.display().to_string(),
+            cubase_version: metadata.version.clone(),
+            architecture: metadata.architecture.clone(),
+            modified,
+            instances,
+        });
+    }
+
+    /// Prints the projects which use a plugin being looked up in the requested order, either as a
+    /// table or in the output format.
+    pub fn print_project_usage(&mut self) -> Result<()> {
+        match self.mode {
+            Mode::WhoUses(SortOrder::Path) => self
+                .project_usage_records
+                .sort_by(|a, b| natord::compare_ignore_case(&a.path, &b.path)),
+            Mode::WhoUses(SortOrder::Date) => self
+                .project_usage_records
+                .sort_by_cached_key(|record| (record.modified.is_none(), record.modified.clone())),
+            Mode::Projects | Mode::Summaries | Mode::History(_) => {}
+        }
+
+        match self.format {
+            Format::Text => {
+                let heading = "Projects Using Matching Plugins".white().on_red();
+                println!();
+                println!("{heading}");
+                println!();
+                print_project_usage_table(&self.project_usage_records);
+            }
+            Format::Json => {
+                let report = ProjectUsageReport {
+                    projects: &self.project_usage_records,
+                    errors: &self.error_reports,
+                };
+                let mut stdout = io::stdout().lock();
+                serde_json::to_writer_pretty(&mut stdout, &report)
+                    .context("unable to write the JSON report")?;
+                writeln!(stdout).context("unable to write the JSON report")?;
+            }
+            Format::Csv => {
+                let mut writer = csv::Writer::from_writer(io::stdout().lock());
+                for record in &self.project_usage_records {
+                    writer
+                        .serialize(record)
+                        .context("unable to write the CSV report")?;
+                }
+                writer.flush().context("unable to write the CSV report")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records a saved version of a project for the history of the project, excluding ignored
+    /// plugins along with their instances.
+    fn record_saved_version(
+        &mut self,
+        project_file_path: &Path,
+        metadata: Metadata,
+        plugins: &[&Plugin],
+        mut instances: Vec<PluginInstance>,
+    ) {
+        if !self.reports_architecture(is_64_bit_architecture(&metadata.architecture)) {
+            return;
+        }
+
+        let plugins = plugins
+            .iter()
+            .map(|&plugin| plugin.clone())
+            .collect::<HashSet<_>>();
+        instances.retain(|instance| plugins.contains(&instance.plugin));
+        let project = Project {
+            metadata,
+            plugins,
+            instances,
+            media: Vec::new(),
+            tracks: Vec::new(),
+        };
+        let modified = self.modified_time(project_file_path);
+
+        self.saved_versions.push(SavedVersion {
+            path: project_file_path.to_path_buf(),
+            version: ProjectVersion::from_path(project_file_path),
+            modified,
+            project,
+        });
+    }
+
+    /// Groups the saved versions of each project by folder and base name, ordering the versions of
+    /// each project and determining the changes between consecutive versions.
+    fn project_histories(&mut self) -> Vec<ProjectHistoryReport> {
+        let mut saved_versions_by_project = HashMap::<(PathBuf, String), Vec<SavedVersion>>::new();
+        for saved_version in self.saved_versions.drain(..) {
+            let project_key = (
+                history::project_folder(&saved_version.path).to_path_buf(),
+                saved_version.version.base_name.to_lowercase(),
+            );
+            saved_versions_by_project
+                .entry(project_key)
+                .or_default()
+                .push(saved_version);
+        }
+
+        let mut project_histories = saved_versions_by_project
+            .into_iter()
+            .map(|((folder, _), mut saved_versions)| {
+                match self.mode {
+                    Mode::History(VersionOrder::Date) => {
+                        saved_versions.sort_by_key(|saved_version| {
+                            (
+                                saved_version.modified.is_none(),
+                                saved_version.modified,
+                                saved_version.version.sort_key(),
+                            )
+                        });
+                    }
+                    _ => saved_versions.sort_by(|a, b| {
+                        a.version
+                            .sort_key()
+                            .cmp(&b.version.sort_key())
+                            .then_with(|| {
+                                natord::compare_ignore_case(
+                                    &a.path.display().to_string(),
+                                    &b.path.display().to_string(),
+                                )
+                            })
+                    }),
+                }
+
+                let base_name = saved_versions
+                    .first()
+                    .map(|saved_version| saved_version.version.base_name.clone())
+                    .unwrap_or_default();
+                let mut previous_project = None;
+                let versions = saved_versions
+                    .iter()
+                    .map(|saved_version| {
+                        let report = project_version_report(saved_version, previous_project);
+                        previous_project = Some(&saved_version.project);
+                        report
+                    })
+                    .collect();
+
+                ProjectHistoryReport {
+                    folder: folder.display().to_string(),
+                    base_name,
+                    versions,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        project_histories.sort_by(|a, b| {
+            natord::compare_ignore_case(&a.folder, &b.folder)
+                .then_with(|| natord::compare_ignore_case(&a.base_name, &b.base_name))
+        });
+        project_histories
+    }
+
+    /// Prints how each project evolved across its saved versions, either as text or in the output
+    /// format.
+    pub fn print_history(&mut self) -> Result<()> {
+        let project_histories = self.project_histories();
+
+        if self.format == Format::Json {
+            let report = HistoryReport {
+                projects: &project_histories,
+                errors: &self.error_reports,
+            };
+            let mut stdout = io::stdout().lock();
+            serde_json::to_writer_pretty(&mut stdout, &report)
+                .context("unable to write the JSON report")?;
+            writeln!(stdout).context("unable to write the JSON report")?;
+            return Ok(());
+        }
+
+        for project_history in &project_histories {
+            let folder = Path::new(&project_history.folder);
+            let history_heading = format!(
+                "History: {}",
+                folder.join(&project_history.base_name).display()
+            )
+            .white()
+            .on_red();
+            println!();
+            println!("{history_heading}");
+            for version_report in &project_history.versions {
+                print_project_version(folder, version_report);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prints the Cubase version and settings of a project, followed by its media files and tracks
+    /// when they are listed.
+    fn print_project_heading(
+        &self,
+        project_file_path: &Path,
+        cubase_version: &str,
+        metadata: &Metadata,
+        media: &[MediaFile],
+        tracks: &[Track],
+    ) {
+        let project_heading = cubase_version.blue();
+        println!("{project_heading}");
+        if let Some(settings) = describe_settings(metadata) {
+            println!("{settings}");
+        }
+        if self.list_media {
+            print_media(&media_reports(project_file_path, media));
+        }
+        if self.list_tracks {
+            print_tracks(tracks);
+        }
+    }
+
+    /// Prints a plugin along with its format, vendor, categories, number of instances and the tracks
+    /// and channels which use it.
+    fn print_plugin(&self, plugin: &Plugin, instances: Option<&PluginInstances<'_>>) {
+        let instance_count = instances.map_or(0, |instances| instances.count);
+        let inactive_count = instances.map_or(0, |instances| instances.inactive);
+
+        let details = iter::once(self.catalog.format(&plugin.guid).to_string())
+            .chain(self.catalog.vendor(&plugin.guid).map(str::to_string))
+            .chain(
+                instances
+                    .into_iter()
+                    .flat_map(PluginInstances::category_names),
+            )
+            .chain(iter::once(pluralise(instance_count, "instance")))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let plugin_line = if self.inactive == Inactive::Highlight && inactive_count > 0 {
+            format!(
+                "    > {} : {} ({details}, {inactive_count} bypassed or deactivated)",
+                plugin.guid, plugin.name,
+            )
+            .yellow()
+        } else {
+            format!("    > {} : {} ({details})", plugin.guid, plugin.name).normal()
+        };
+
+        match self.plugin_status(plugin) {
+            Some(status @ (PluginStatus::Missing | PluginStatus::NameOnly)) => {
+                println!("{plugin_line} {}", status_label(status));
+            }
+            _ => println!("{plugin_line}"),
+        }
+
+        for channel in instances
+            .into_iter()
+            .flat_map(|instances| &instances.channels)
+        {
+            println!("        - {} ({})", channel.name, channel.kind);
+        }
+
+        for instance in instances
+            .into_iter()
+            .flat_map(|instances| &instances.instances)
+        {
+            let Some(preset_name) = instance
+                .state
+                .as_ref()
+                .and_then(|state| state.preset_name.as_ref())
+            else {
+                continue;
+            };
+            match &instance.channel {
+                Some(channel) => println!("        * {preset_name} (preset on {})", channel.name),
+                None => println!("        * {preset_name} (preset)"),
+            }
+        }
+    }
+
+    /// Writes the state of each instance of the displayed plugins of a project to preset files when
+    /// presets are exported, returning the path of each preset keyed by the offset of its instance.
+    fn export_presets(
+        &mut self,
+        project_file_path: &Path,
+        plugins: &[&Plugin],
+        plugin_instances: &HashMap<&Plugin, PluginInstances<'_>>,
+    ) -> HashMap<usize, PathBuf> {
+        let Some(preset_directories) = &mut self.preset_directories else {
+            return HashMap::new();
+        };
+        let project_preset_directory = preset_directories.project_directory(project_file_path);
+
+        let instances = plugins
+            .iter()
+            .filter(|plugin| !self.only_show_filtered || self.matches_filters(plugin))
+            .filter_map(|plugin| plugin_instances.get(plugin))
+            .flat_map(|instances| instances.instances.iter().copied())
+            .collect::<Vec<_>>();
+
+        match preset::export_presets(&project_preset_directory, &instances) {
+            Ok(preset_paths) => preset_paths,
+            Err(error) => {
+                self.report_file_error(project_file_path, &error);
+                HashMap::new()
+            }
+        }
+    }
+
+    fn plugin_reports(
+        &self,
+        plugins: &[&Plugin],
+        plugin_instances: &HashMap<&Plugin, PluginInstances<'_>>,
+        preset_paths: &HashMap<usize, PathBuf>,
+    ) -> Vec<PluginReport> {
+        plugins
+            .iter()
+            .filter(|plugin| !self.only_show_filtered || self.matches_filters(plugin))
+            .map(|&plugin| {
+                let instances = plugin_instances.get(plugin);
+                PluginReport {
+                    plugin: plugin.clone(),
+                    format: self.catalog.format(&plugin.guid),
+                    catalog: self.catalog.get(&plugin.guid).cloned(),
+                    instances: instances.map_or(0, |instances| instances.count),
+                    inactive_instances: instances.map_or(0, |instances| instances.inactive),
+                    status: self.plugin_status(plugin),
+                    categories: instances
+                        .into_iter()
+                        .flat_map(|instances| &instances.categories)
+                        .map(|&(category, _)| category)
+                        .collect(),
+                    channels: instances
+                        .into_iter()
+                        .flat_map(|instances| &instances.channels)
+                        .map(|&channel| channel.clone())
+                        .collect(),
+                    states: self.state_encoding.map(|state_encoding| {
+                        instances
+                            .into_iter()
+                            .flat_map(|instances| &instances.instances)
+                            .filter_map(|instance| {
+                                let preset_path = preset_paths.get(&instance.offset);
+                                StateReport::new(
+                                    instance,
+                                    state_encoding,
+                                    preset_path.map(PathBuf::as_path),
+                                )
+                            })
+                            .collect()
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// Adds the counts of a project to the summaries, keeping them so that they may be removed if
+    /// the project changes.
+    fn count_project(&mut self, project_file_path: &Path, project_counts: ProjectCounts) {
+        if !self
+            .summary_file_kinds
+            .contains(&ProjectFileKind::from_path(project_file_path))
+        {
+            return;
+        }
+
+        *self
+            .cubase_version_counts
+            .entry(project_counts.cubase_version.clone())
+            .or_default() += 1;
+        *self
+            .sample_rate_counts
+            .entry(project_counts.sample_rate.clone())
+            .or_default() += 1;
+
+        for (plugin, instance_count) in &project_counts.plugins {
+            self.count_plugin(plugin, project_counts.is_64_bit, *instance_count);
+        }
+
+        for &(category, instance_count) in &project_counts.categories {
+            let usage = self.category_counts.entry(category).or_default();
+            usage.projects += 1;
+            usage.instances += instance_count;
+        }
+
+        for (vendor, instance_count) in &project_counts.vendors {
+            let usage = self.vendor_counts.entry(vendor.clone()).or_default();
+            usage.projects += 1;
+            usage.instances += instance_count;
+        }
+
+        self.project_counts
+            .insert(project_file_path.to_path_buf(), project_counts);
+    }
+
+    /// Removes the counts of a previously counted project from the summaries.
+    fn uncount_project(&mut self, project_file_path: &Path) {
+        let Some(project_counts) = self.project_counts.remove(project_file_path) else {
+            return;
+        };
+
+        uncount_key(
+            &mut self.cubase_version_counts,
+            &project_counts.cubase_version,
+        );
+        uncount_key(&mut self.sample_rate_counts, &project_counts.sample_rate);
+
+        let architecture_plugin_counts = if project_counts.is_64_bit {
+            &mut self.plugin_counts_64
+        } else {
+            &mut self.plugin_counts_32
+        };
+
+        for (plugin, instance_count) in &project_counts.plugins {
+            for plugin_counts in [
+                &mut self.plugin_counts,
+                &mut *architecture_plugin_counts,
+                &mut self.missing_plugin_counts,
+            ] {
+                uncount_usage(plugin_counts, plugin, *instance_count);
+            }
+        }
+
+        for (category, instance_count) in &project_counts.categories {
+            uncount_usage(&mut self.category_counts, category, *instance_count);
+        }
+
+        for (vendor, instance_count) in &project_counts.vendors {
+            uncount_usage(&mut self.vendor_counts, vendor, *instance_count);
+        }
+    }
+
+    fn count_plugin(&mut self, plugin: &Plugin, is_64_bit: bool, instance_count: i32) {
+        let architecture_plugin_counts = if is_64_bit {
+            &mut self.plugin_counts_64
+        } else {
+            &mut self.plugin_counts_32
+        };
+
+        for plugin_counts in [&mut self.plugin_counts, architecture_plugin_counts] {
+            let usage = plugin_counts.entry(plugin.clone()).or_default();
+            usage.projects += 1;
+            usage.instances += instance_count;
+        }
+
+        if self
+            .plugin_status(plugin)
+            .is_some_and(|status| status != PluginStatus::Installed)
+        {
+            let usage = self
+                .missing_plugin_counts
+                .entry(plugin.clone())
+                .or_default();
+            usage.projects += 1;
+            usage.instances += instance_count;
+        }
+    }
+
+    pub fn print_summaries(&self) {
+        self.print_plugin_summary(&self.plugin_counts_32, "32-bit");
+        self.print_plugin_summary(&self.plugin_counts_64, "64-bit");
+        self.print_plugin_summary(&self.plugin_counts, "all");
+        self.print_missing_plugin_summary();
+        self.print_vst2_plugin_summary();
+        self.print_vendor_summary();
+        self.print_category_summary();
+        print_count_summary(
+            &self.cubase_version_counts,
+            "Summary: Cubase Versions Used In Projects",
+        );
+        print_count_summary(
+            &self.sample_rate_counts,
+            "Summary: Sample Rates Used In Projects",
+        );
+    }
+
+    fn print_plugin_summary(
+        &self,
+        plugin_counts: &HashMap<Plugin, PluginUsage>,
+        description: &str,
+    ) {
+        if plugin_counts.is_empty() {
+            return;
+        }
+
+        let summary_heading = format!("Summary: Plugins Used In {description} Projects")
+            .white()
+            .on_red();
+
+        println!();
+        println!("{summary_heading}");
+        println!();
+
+        let mut sorted_plugin_counts = Vec::from_iter(plugin_counts);
+        sorted_plugin_counts
+            .sort_by_cached_key(|(plugin, _)| (plugin.name.to_lowercase(), &plugin.guid));
+
+        for (plugin, usage) in &sorted_plugin_counts {
+            if !self.only_show_filtered || self.matches_filters(plugin) {
+                println!(
+                    "    > {} : {} ({}, {})",
+                    plugin.guid,
+                    plugin.name,
+                    pluralise(usage.projects, "project"),
+                    pluralise(usage.instances, "instance")
+                );
+            }
+        }
+    }
+
+    fn print_vst2_plugin_summary(&self) {
+        let vst2_plugin_counts = self.vst2_plugin_count_summary();
+        if vst2_plugin_counts.is_empty() {
+            return;
+        }
+
+        let summary_heading = "Summary: VST2 Plugins Used In Projects".white().on_red();
+
+        println!();
+        println!("{summary_heading}");
+        println!();
+
+        for vst2_plugin_count in vst2_plugin_counts {
+            let plugin_count = vst2_plugin_count.plugin_count;
+            println!(
+                "    > {} : {} (unique ID '{}', {}, {})",
+                plugin_count.guid,
+                plugin_count.name,
+                vst2_plugin_count.unique_id,
+                pluralise(plugin_count.count, "project"),
+                pluralise(plugin_count.instances, "instance")
+            );
+        }
+    }
+
+    fn print_vendor_summary(&self) {
+        let vendor_counts = self.vendor_count_summary();
+        if vendor_counts.is_empty() {
+            return;
+        }
+
+        let summary_heading = "Summary: Plugin Vendors Used In Projects".white().on_red();
+
+        println!();
+        println!("{summary_heading}");
+        println!();
+
+        for vendor_count in vendor_counts {
+            println!(
+                "    > {} ({}, {})",
+                vendor_count.vendor.unwrap_or(UNKNOWN_VENDOR),
+                pluralise(vendor_count.count, "project"),
+                pluralise(vendor_count.instances, "instance")
+            );
+            for plugin_count in vendor_count.plugins {
+                println!(
+                    "        - {} : {} ({}, {}, {})",
+                    plugin_count.guid,
+                    plugin_count.name,
+                    if plugin_count.stock {
+                        "stock"
+                    } else {
+                        "third-party"
+                    },
+                    pluralise(plugin_count.count, "project"),
+                    pluralise(plugin_count.instances, "instance")
+                );
+            }
+        }
+    }
+
+    fn print_category_summary(&self) {
+        if self.category_counts.is_empty() {
+            return;
+        }
+
+        let summary_heading = "Summary: Plugin Categories Used In Projects"
+            .white()
+            .on_red();
+
+        println!();
+        println!("{summary_heading}");
+        println!();
+
+        for category_count in CategoryCount::sorted(&self.category_counts) {
+            println!(
+                "    > {} ({}, {})",
+                category_count.category,
+                pluralise(category_count.count, "project"),
+                pluralise(category_count.instances, "instance")
+            );
+        }
+    }
+
+    fn print_missing_plugin_summary(&self) {
+        if self.missing_plugin_counts.is_empty() {
+            return;
+        }
+
+        let summary_heading = "Summary: Plugins Missing From Inventory".white().on_red();
+
+        println!();
+        println!("{summary_heading}");
+        println!();
+
+        for missing_plugin_count in self.missing_plugin_count_summary() {
+            let plugin_count = missing_plugin_count.plugin_count;
+            println!(
+                "    > {} : {} ({}, {}) {}",
+                plugin_count.guid,
+                plugin_count.name,
+                pluralise(plugin_count.count, "project"),
+                pluralise(plugin_count.instances, "instance"),
+                status_label(missing_plugin_count.status)
+            );
+        }
+    }
+
+    /// Prints all reported projects, errors and summaries as a single JSON document.
+    pub fn print_json(&self) -> Result<()> {
+        let report = Report {
+            projects: &self.project_reports,
+            errors: &self.error_reports,
+            summaries: self.summaries(),
+        };
+
+        let mut stdout = io::stdout().lock();
+        serde_json::to_writer_pretty(&mut stdout, &report)
+            .context("unable to write the JSON report")?;
+        writeln!(stdout).context("unable to write the JSON report")?;
+
+        Ok(())
+    }
+
+    /// Prints one CSV record for each plugin used in each reported project.
+    pub fn print_csv(&self) -> Result<()> {
+        let mut writer = csv::Writer::from_writer(io::stdout().lock());
+        for record in PluginUsageRecord::from_projects(&self.project_reports) {
+            writer
+                .serialize(record)
+                .context("unable to write the CSV report")?;
+        }
+        writer.flush().context("unable to write the CSV report")?;
+
+        Ok(())
+    }
+
+    /// Writes the plugin and Cubase version summary counts to a CSV file.
+    pub fn write_summary_csv(&self, summary_csv_path: &Path) -> Result<()> {
+        let context = || {
+            format!(
+                "unable to write summary CSV file '{}'",
+                summary_csv_path.display().to_string().blue()
+            )
+        };
+
+        let writer = csv::Writer::from_path(summary_csv_path).with_context(context)?;
+        self.write_summary_records(writer).with_context(context)
+    }
+
+    /// Prints the plugin and Cubase version summary counts as CSV records.
+    pub fn print_summary_csv(&self) -> Result<()> {
+        let writer = csv::Writer::from_writer(io::stdout().lock());
+        self.write_summary_records(writer)
+            .context("unable to write the CSV summaries")
+    }
+
+    fn write_summary_records(&self, mut writer: csv::Writer<impl Write>) -> csv::Result<()> {
+        let summaries = self.summaries();
+        for record in SummaryRecord::from_summaries(&summaries) {
+            writer.serialize(record)?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn summaries(&self) -> Summaries<'_> {
+        Summaries {
+            plugins_32_bit: self.plugin_count_summary(&self.plugin_counts_32),
+            plugins_64_bit: self.plugin_count_summary(&self.plugin_counts_64),
+            plugins: self.plugin_count_summary(&self.plugin_counts),
+            missing_plugins: self
+                .inventory
+                .is_some()
+                .then(|| self.missing_plugin_count_summary()),
+            vst2_plugins: self.vst2_plugin_count_summary(),
+            vendors: self.vendor_count_summary(),
+            categories: CategoryCount::sorted(&self.category_counts),
+            cubase_versions: CubaseVersionCount::sorted(&self.cubase_version_counts),
+            sample_rates: SampleRateCount::sorted(&self.sample_rate_counts),
+        }
+    }
+
+    fn plugin_count_summary<'a>(
+        &'a self,
+        plugin_counts: &'a HashMap<Plugin, PluginUsage>,
+    ) -> Vec<PluginCount<'a>> {
+        PluginCount::sorted(
+            plugin_counts
+                .iter()
+                .filter(|(plugin, _)| !self.only_show_filtered || self.matches_filters(plugin)),
+            &self.catalog,
+        )
+    }
+
+    fn vendor_count_summary(&self) -> Vec<VendorCount<'_>> {
+        VendorCount::sorted(
+            &self.vendor_counts,
+            self.plugin_count_summary(&self.plugin_counts),
+        )
+    }
+
+    fn vst2_plugin_count_summary(&self) -> Vec<Vst2PluginCount<'_>> {
+        Vst2PluginCount::from_plugin_counts(self.plugin_count_summary(&self.plugin_counts))
+    }
+
+    fn missing_plugin_count_summary(&self) -> Vec<MissingPluginCount<'_>> {
+        let Some(inventory) = &self.inventory else {
+            return Vec::new();
+        };
+
+        self.plugin_count_summary(&self.missing_plugin_counts)
+            .into_iter()
+            .map(|plugin_count| MissingPluginCount {
+                status: inventory.status(plugin_count.guid, plugin_count.name),
+                plugin_count,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use similar_asserts::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn read_project(filename: &str) -> Project {
+        read_cubase_project_file(&PathBuf::from("testdata").join(filename), false).unwrap()
+    }
+
+    fn processor() -> Processor {
+        let thread_pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        Processor::new(
+            Config::default(),
+            Vec::new(),
+            Vec::new(),
+            false,
+            Inactive::Include,
+            Format::Json,
+            thread_pool,
+        )
+        .with_inventory(Inventory::default())
+    }
+
+    /// Scans each project in turn and returns the resulting summaries.
+    fn summaries(projects: &[(&str, &str)]) -> serde_json::Value {
+        let mut processor = processor();
+        for (project_file_path, filename) in projects {
+            processor.process_cubase_project(Path::new(project_file_path), read_project(filename));
+        }
+        serde_json::to_value(processor.summaries()).unwrap()
+    }
+
+    /// Creates a directory of project files copied from the test data, returning the directory
+    /// along with its path.
+    fn project_directory() -> (TempDir, String) {
+        let directory = TempDir::new().unwrap();
+        for (project_file_path, filename) in [
+            ("Song/Song.cpr", "Example Project (Cubase 14).cpr"),
+            ("Song/Song-01.cpr", "Example Project (Cubase 13).cpr"),
+            ("Other/Other.cpr", "Example Project (Cubase 11).cpr"),
+        ] {
+            let project_file_path = directory.path().join(project_file_path);
+            fs::create_dir_all(project_file_path.parent().unwrap()).unwrap();
+            fs::copy(PathBuf::from("testdata").join(filename), project_file_path).unwrap();
+        }
+        let directory_path = directory.path().display().to_string();
+        (directory, directory_path)
+    }
+
+    /// Lists the paths of the projects and errors which were reported, relative to a directory.
+    fn reported_paths(processor: &Processor, directory_path: &str) -> (Vec<String>, Vec<String>) {
+        let relative_path = |path: &String| {
+            path.strip_prefix(directory_path)
+                .unwrap_or(path)
+                .replace('\\', "/")
+        };
+        (
+            processor
+                .project_reports
+                .iter()
+                .map(|report| relative_path(&report.path))
+                .collect(),
+            processor
+                .error_reports
+                .iter()
+                .map(|report| relative_path(&report.path))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_process_overlapping_project_paths() {
+        let (_directory, directory_path) = project_directory();
+        let mut processor = processor();
+
+        processor.process_cubase_project_paths(&[
+            format!("{directory_path}/Song/Song.cpr"),
+            format!("{directory_path}/Song"),
+            format!("{directory_path}/**"),
+        ]);
+
+        assert_eq!(
+            reported_paths(&processor, &directory_path),
+            (
+                vec![
+                    "/Song/Song.cpr".to_string(),
+                    "/Song/Song-01.cpr".to_string(),
+                    "/Other/Other.cpr".to_string(),
+                ],
+                Vec::new()
+            )
+        );
+    }
+
+    #[test]
+    fn test_process_glob_patterns() {
+        let (_directory, directory_path) = project_directory();
+        let mut processor = processor();
+
+        processor.process_cubase_project_paths(&[
+            format!("{directory_path}/*/Song*.cpr"),
+            format!("{directory_path}/*/Missing*.cpr"),
+            format!("{directory_path}/Missing.cpr"),
+        ]);
+
+        assert_eq!(
+            reported_paths(&processor, &directory_path),
+            (
+                vec![
+                    "/Song/Song-01.cpr".to_string(),
+                    "/Song/Song.cpr".to_string()
+                ],
+                vec!["/*/Missing*.cpr".to_string(), "/Missing.cpr".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn test_process_stdin_project_paths() {
+        let (_directory, directory_path) = project_directory();
+        let mut processor = processor();
+        let mut project_files = ProjectFileSet::default();
+
+        processor
+            .process_stdin(
+                format!("{directory_path}/Song\n\n{directory_path}/Song/Song.cpr\n").into_bytes(),
+                &mut project_files,
+            )
+            .unwrap();
+        processor.process_project_files(&project_files.sources);
+
+        assert_eq!(
+            reported_paths(&processor, &directory_path),
+            (
+                vec![
+                    "/Song/Song-01.cpr".to_string(),
+                    "/Song/Song.cpr".to_string()
+                ],
+                Vec::new()
+            )
+        );
+    }
+
+    #[test]
+    fn test_process_stdin_project() {
+        let mut processor = processor();
+        let mut project_files = ProjectFileSet::default();
+        let project_bytes =
+            fs::read(PathBuf::from("testdata").join("Example Project (Cubase 14).cpr")).unwrap();
+
+        processor
+            .process_stdin(project_bytes, &mut project_files)
+            .unwrap();
+
+        assert!(processor.project_reports.is_empty());
+
+        processor.process_project_files(&project_files.sources);
+
+        assert_eq!(
+            reported_paths(&processor, ""),
+            (vec![STDIN_PROJECT_PATH.to_string()], Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_process_stdin_project_in_order() {
+        let (_directory, directory_path) = project_directory();
+        let mut processor = processor();
+        let mut project_files = ProjectFileSet::default();
+        let project_bytes =
+            fs::read(PathBuf::from("testdata").join("Example Project (Cubase 14).cpr")).unwrap();
+
+        processor.find_project_path_argument(
+            &format!("{directory_path}/Song/Song.cpr"),
+            &mut project_files,
+        );
+        processor
+            .process_stdin(project_bytes, &mut project_files)
+            .unwrap();
+        processor.find_project_path_argument(
+            &format!("{directory_path}/Other/Other.cpr"),
+            &mut project_files,
+        );
+        processor.process_project_files(&project_files.sources);
+
+        assert_eq!(
+            reported_paths(&processor, &directory_path),
+            (
+                vec![
+                    "/Song/Song.cpr".to_string(),
+                    STDIN_PROJECT_PATH.to_string(),
+                    "/Other/Other.cpr".to_string(),
+                ],
+                Vec::new()
+            )
+        );
+    }
+
+    #[test]
+    fn test_process_stdin_invalid() {
+        let mut processor = processor();
+        let mut project_files = ProjectFileSet::default();
+
+        let error = processor
+            .process_stdin(vec![0xFF, 0xFE], &mut project_files)
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "standard input is neither a project nor a list of project paths"
+        );
+    }
+
+    #[test]
+    fn test_diff_filtered_projects() {
+        let mut config = Config::default();
+        config.plugins.ignore_stock = true;
+        let thread_pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let processor = Processor::new(
+            config,
+            Vec::new(),
+            Vec::new(),
+            false,
+            Inactive::Include,
+            Format::Json,
+            thread_pool,
+        );
+
+        let old_project = read_project("Example Project (Cubase 5 64-bit).cpr");
+        let new_project = read_project("Example Project (Cubase 14).cpr");
+        assert!(
+            !ProjectDiff::new(&old_project, &new_project)
+                .added_plugins
+                .is_empty()
+        );
+
+        let diff = ProjectDiff::new(
+            &processor.filter_project(old_project),
+            &processor.filter_project(new_project),
+        );
+
+        assert!(diff.added_plugins.is_empty());
+        assert!(diff.removed_plugins.is_empty());
+        assert!(diff.renamed_plugins.is_empty());
+    }
+
+    #[test]
+    fn test_watch_targets() {
+        let (_directory, directory_path) = project_directory();
+        let processor = processor();
+
+        let watch_targets = processor
+            .watch_targets(&[
+                format!("{directory_path}/Song/Song.cpr"),
+                format!("{directory_path}/Oth*"),
+                STDIN_PATH.to_string(),
+            ])
+            .unwrap();
+        let canonical_directory = fs::canonicalize(&directory_path).unwrap();
+
+        assert_eq!(
+            watch_targets.resolve(&canonical_directory.join("Song").join("Song.cpr")),
+            Some(PathBuf::from(format!("{directory_path}/Song/Song.cpr")))
+        );
+        assert_eq!(
+            watch_targets.resolve(&canonical_directory.join("Song").join("Song-01.cpr")),
+            None
+        );
+        assert_eq!(
+            watch_targets.resolve(&Path::new(&directory_path).join("Other").join("New.cpr")),
+            Some(Path::new(&directory_path).join("Other").join("New.cpr"))
+        );
+    }
+
+    #[test]
+    fn test_watch_targets_without_directories_or_project_files() {
+        let processor = processor();
+
+        let error = processor
+            .watch_targets(&[STDIN_PATH.to_string(), "Missing.cpr".to_string()])
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "watch mode requires at least one directory or project file to watch"
+        );
+    }
+
+    #[test]
+    fn test_is_path_ignored() {
+        let thread_pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let processor = Processor::new(
+            Config::default(),
+            [
+                Pattern::new("**/Older Versions*/**/*.cpr").unwrap(),
+                Pattern::new("**/Busted/*.cpr").unwrap(),
+            ],
+            Vec::new(),
+            false,
+            Inactive::Include,
+            Format::Json,
+            thread_pool,
+        );
+
+        assert!(processor.is_path_ignored(Path::new("Songs/Song/Older Versions/Song-01.cpr")));
+        assert!(processor.is_path_ignored(Path::new("Songs/Busted/Busted.cpr")));
+        assert!(!processor.is_path_ignored(Path::new("Songs/Song/Song.cpr")));
+
+        let processor = processor.with_history(VersionOrder::Version);
+
+        assert!(!processor.is_path_ignored(Path::new("Songs/Song/Older Versions/Song-01.cpr")));
+        assert!(!processor.is_path_ignored(Path::new("Songs/Song/Older Versions 2/Song-01.cpr")));
+        assert!(processor.is_path_ignored(Path::new("Songs/Busted/Busted.cpr")));
+        assert!(processor.is_path_ignored(Path::new("Songs/Busted/Older Versions/Busted-01.cpr")));
+        assert!(processor.is_path_ignored(Path::new("Songs/Busted/Auto Saves/Busted-01.cpr")));
+    }
+
+    #[test]
+    fn test_recount_modified_project() {
+        let mut processor = processor();
+        processor.process_cubase_project(
+            Path::new("Song/Song.cpr"),
+            read_project("Example Project (Cubase 4.5 32-bit).cpr"),
+        );
+        processor.process_cubase_project(
+            Path::new("Other/Other.cpr"),
+            read_project("Example Project (Cubase 11).cpr"),
+        );
+
+        processor.uncount_project(Path::new("Song/Song.cpr"));
+        processor.process_cubase_project(
+            Path::new("Song/Song.cpr"),
+            read_project("Example Project (Cubase 14).cpr"),
+        );
+
+        assert_eq!(
+            serde_json::to_value(processor.summaries()).unwrap(),
+            summaries(&[
+                ("Other/Other.cpr", "Example Project (Cubase 11).cpr"),
+                ("Song/Song.cpr", "Example Project (Cubase 14).cpr"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_uncount_removed_project() {
+        let mut processor = processor();
+        processor.process_cubase_project(
+            Path::new("Song/Song.cpr"),
+            read_project("Example Project (Cubase 4.5 32-bit).cpr"),
+        );
+        processor.process_cubase_project(
+            Path::new("Other/Other.cpr"),
+            read_project("Example Project (Cubase 14).cpr"),
+        );
+
+        processor.uncount_project(Path::new("Song/Song.cpr"));
+
+        assert_eq!(
+            serde_json::to_value(processor.summaries()).unwrap(),
+            summaries(&[("Other/Other.cpr", "Example Project (Cubase 14).cpr")])
+        );
+
+        processor.uncount_project(Path::new("Other/Other.cpr"));
+
+        assert_eq!(
+            serde_json::to_value(processor.summaries()).unwrap(),
+            summaries(&[])
+        );
+        assert!(processor.project_counts.is_empty());
+    }
+
+    #[test]
+    fn test_uncount_unknown_project() {
+        let mut processor = processor();
+        processor.process_cubase_project(
+            Path::new("Song/Song.cpr"),
+            read_project("Example Project (Cubase 14).cpr"),
+        );
+
+        processor.uncount_project(Path::new("Other/Other.cpr"));
+
+        assert_eq!(
+            serde_json::to_value(processor.summaries()).unwrap(),
+            summaries(&[("Song/Song.cpr", "Example Project (Cubase 14).cpr")])
+        );
     }
 }
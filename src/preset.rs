@@ -0,0 +1,259 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use colored::Colorize as _;
+use cubase_project_plugins::PluginInstance;
+
+/// The extension of preset files which may be loaded into plugins in Cubase.
+const PRESET_EXTENSION: &str = "vstpreset";
+
+/// Replaces characters which aren't allowed in file names on common platforms.
+fn sanitise_file_name(name: &str) -> String {
+    name.chars()
+        .map(|character| match character {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            _ if character.is_control() => '_',
+            _ => character,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// The directory which presets are exported to, containing a directory for each project file.
+pub struct PresetDirectories {
+    /// Path of the directory which presets are exported to.
+    directory: PathBuf,
+    /// The directory which the presets of each project file are written to.
+    project_directories: HashMap<PathBuf, PathBuf>,
+    /// The lowercase names of the directories which have been assigned to project files.
+    directory_names: HashSet<String>,
+}
+
+impl PresetDirectories {
+    pub fn new(directory: PathBuf) -> Self {
+        Self {
+            directory,
+            project_directories: HashMap::new(),
+            directory_names: HashSet::new(),
+        }
+    }
+
+    /// Determines the directory which the presets of a project file are written to.
+    ///
+    /// Directories are named after the file name of their project file, including its extension so
+    /// that backups and auto-saves don't share the directory of their project file. A number is
+    /// appended when project files within different directories share the same file name.
+    pub fn project_directory(&mut self, project_file_path: &Path) -> PathBuf {
+        if let Some(project_directory) = self.project_directories.get(project_file_path) {
+            return project_directory.clone();
+        }
+
+        let file_name = project_file_path
+            .file_name()
+            .map_or_else(|| "Project".into(), |file_name| file_name.to_string_lossy());
+        let base_name = sanitise_file_name(&file_name);
+        let mut directory_name = base_name.clone();
+        let mut number = 2;
+        while !self.directory_names.insert(directory_name.to_lowercase()) {
+            directory_name = format!("{base_name} ({number})");
+            number += 1;
+        }
+
+        let project_directory = self.directory.join(directory_name);
+        self.project_directories
+            .insert(project_file_path.to_path_buf(), project_directory.clone());
+        project_directory
+    }
+}
+
+/// Writes the state of each plugin instance of a project to a preset file within the given
+/// directory, returning the path of each preset keyed by the offset of its instance.
+///
+/// Presets are named after the channel and plugin of each instance, with a number appended when
+/// several instances of a plugin belong to the same channel or to channels whose names only differ
+/// in case.
+pub fn export_presets(
+    project_preset_directory: &Path,
+    instances: &[&PluginInstance],
+) -> Result<HashMap<usize, PathBuf>> {
+    let mut preset_paths = HashMap::new();
+    let mut preset_names = HashSet::new();
+    for instance in instances {
+        let Some(state) = &instance.state else {
+            continue;
+        };
+        if state.component.is_empty() && state.controller.is_empty() {
+            continue;
+        }
+
+        let base_name = sanitise_file_name(&instance.channel.as_ref().map_or_else(
+            || instance.plugin.name.clone(),
+            |channel| format!("{} - {}", channel.name, instance.plugin.name),
+        ));
+        let mut preset_name = base_name.clone();
+        let mut number = 2;
+        while !preset_names.insert(preset_name.to_lowercase()) {
+            preset_name = format!("{base_name} ({number})");
+            number += 1;
+        }
+
+        if preset_paths.is_empty() {
+            fs::create_dir_all(project_preset_directory).with_context(|| {
+                format!(
+                    "unable to create preset directory '{}'",
+                    project_preset_directory.display().to_string().blue()
+                )
+            })?;
+        }
+
+        let preset_path =
+            project_preset_directory.join(format!("{preset_name}.{PRESET_EXTENSION}"));
+        fs::write(&preset_path, state.to_vst_preset(&instance.plugin.guid)).with_context(|| {
+            format!(
+                "unable to write preset file '{}'",
+                preset_path.display().to_string().blue()
+            )
+        })?;
+        preset_paths.insert(instance.offset, preset_path);
+    }
+
+    Ok(preset_paths)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use cubase_project_plugins::{Channel, ChannelKind, Plugin, PluginCategory, PluginState};
+    use similar_asserts::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn instance(channel_name: Option<&str>, offset: usize, component: &[u8]) -> PluginInstance {
+        PluginInstance {
+            plugin: Plugin {
+                guid: "D39D5B69D6AF42FA1234567868495645".to_string(),
+                name: "Hive".to_string(),
+            },
+            channel: channel_name.map(|name| Channel {
+                name: name.to_string(),
+                kind: ChannelKind::RackInstrument,
+            }),
+            category: PluginCategory::Instrument,
+            offset,
+            active: true,
+            bypassed: false,
+            state: Some(PluginState {
+                component: component.to_vec(),
+                controller: Vec::new(),
+                preset_name: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_project_directory() {
+        let mut preset_directories = PresetDirectories::new(PathBuf::from("Presets"));
+
+        let project_directories = [
+            "Song/Song.cpr",
+            "Song/Song.bak",
+            "Song/Song-01.cpr",
+            "Song/Auto Saves/Song-01.csh",
+            "A/Mix.cpr",
+            "B/Mix.cpr",
+            "C/mix.cpr",
+            "A/Mix.cpr",
+        ]
+        .map(|project_file_path| {
+            preset_directories.project_directory(Path::new(project_file_path))
+        });
+
+        assert_eq!(
+            project_directories,
+            [
+                "Song.cpr",
+                "Song.bak",
+                "Song-01.cpr",
+                "Song-01.csh",
+                "Mix.cpr",
+                "Mix.cpr (2)",
+                "mix.cpr (3)",
+                "Mix.cpr",
+            ]
+            .map(|directory_name| Path::new("Presets").join(directory_name))
+        );
+    }
+
+    #[test]
+    fn test_export_presets() {
+        let directory = TempDir::new().unwrap();
+        let project_preset_directory = directory.path().join("Song.cpr");
+        let instances = [
+            instance(Some("Hive 1"), 10, b"first"),
+            instance(Some("Hive 1"), 20, b"second"),
+            instance(None, 30, b"third"),
+            instance(Some("Hive 2"), 40, b""),
+            instance(Some("hive 1"), 50, b"fourth"),
+        ];
+
+        let preset_paths = export_presets(
+            &project_preset_directory,
+            &instances.iter().collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        let mut preset_paths = preset_paths.into_iter().collect::<Vec<_>>();
+        preset_paths.sort();
+        assert_eq!(
+            preset_paths,
+            vec![
+                (10, project_preset_directory.join("Hive 1 - Hive.vstpreset")),
+                (
+                    20,
+                    project_preset_directory.join("Hive 1 - Hive (2).vstpreset")
+                ),
+                (30, project_preset_directory.join("Hive.vstpreset")),
+                (
+                    50,
+                    project_preset_directory.join("hive 1 - Hive (3).vstpreset")
+                ),
+            ]
+        );
+        for (offset, preset_path) in preset_paths {
+            let instance = instances
+                .iter()
+                .find(|instance| instance.offset == offset)
+                .unwrap();
+            assert_eq!(
+                fs::read(preset_path).unwrap(),
+                instance
+                    .state
+                    .as_ref()
+                    .unwrap()
+                    .to_vst_preset(&instance.plugin.guid)
+            );
+        }
+    }
+
+    #[test]
+    fn test_export_presets_without_states() {
+        let directory = TempDir::new().unwrap();
+        let project_preset_directory = directory.path().join("Song.cpr");
+        let instances = [instance(Some("Hive 1"), 10, b"")];
+
+        let preset_paths = export_presets(
+            &project_preset_directory,
+            &instances.iter().collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        assert!(preset_paths.is_empty());
+        assert!(!project_preset_directory.exists());
+    }
+}
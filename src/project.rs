@@ -1,7 +1,29 @@
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    fmt,
+    hash::{Hash, Hasher},
+};
 
-/// Contains information about the Cubase version used to create the project.
-#[derive(Debug, Eq, PartialEq, Hash)]
+use serde::{Deserialize, Serialize};
+
+/// The bytes which begin the GUID that Cubase assigns to each VST2 plugin it wraps.
+const VST2_GUID_PREFIX: &[u8] = b"VST";
+
+/// The length of the unique ID of a VST2 plugin which follows the prefix of its GUID.
+const VST2_UNIQUE_ID_LENGTH: usize = 4;
+
+/// The length of a GUID in bytes.
+const GUID_LENGTH: usize = 16;
+
+/// The version of the `.vstpreset` file format which is written.
+const VST_PRESET_VERSION: i32 = 1;
+
+/// The length of the class ID of the plugin stored in a `.vstpreset` file.
+const VST_PRESET_CLASS_ID_LENGTH: usize = 32;
+
+/// Contains information about the Cubase version used to create the project along with the main
+/// settings of the project.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
 pub struct Metadata {
     /// Application name (this is always "Cubase").
     pub application: String,
@@ -11,10 +33,70 @@ pub struct Metadata {
     pub release_date: String,
     /// System architecture used to create the project.
     pub architecture: String,
+    /// Initial tempo of the project.
+    #[serde(default)]
+    pub tempo: Option<Tempo>,
+    /// Whether the tempo track is active rather than the project using a fixed tempo.
+    #[serde(default)]
+    pub tempo_track_active: Option<bool>,
+    /// Initial time signature of the project.
+    #[serde(default)]
+    pub time_signature: Option<TimeSignature>,
+    /// Sample rate of the project in hertz.
+    #[serde(default)]
+    pub sample_rate: Option<u32>,
+    /// Bit depth which audio is recorded with.
+    #[serde(default)]
+    pub bit_depth: Option<u32>,
+}
+
+/// The tempo of a project, such as 120 BPM.
+///
+/// Tempos are compared and hashed by the bits of their value so that metadata may be hashed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Tempo {
+    /// Number of beats per minute.
+    pub bpm: f64,
+}
+
+impl PartialEq for Tempo {
+    fn eq(&self, other: &Self) -> bool {
+        self.bpm.to_bits() == other.bpm.to_bits()
+    }
+}
+
+impl Eq for Tempo {}
+
+impl Hash for Tempo {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bpm.to_bits().hash(state);
+    }
+}
+
+impl fmt::Display for Tempo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} BPM", self.bpm)
+    }
+}
+
+/// The time signature of a project, such as 4/4.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct TimeSignature {
+    /// Number of beats in each bar.
+    pub numerator: u32,
+    /// Note value of each beat.
+    pub denominator: u32,
+}
+
+impl fmt::Display for TimeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
 }
 
 /// Represents a plugin within a Cubase project.
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
 pub struct Plugin {
     /// Globally unique identifier for the plugin.
     pub guid: String,
@@ -22,11 +104,473 @@ pub struct Plugin {
     pub name: String,
 }
 
+/// The format of a plugin as determined from its GUID.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum PluginFormat {
+    /// A VST2 plugin which is wrapped by Cubase.
+    Vst2 {
+        /// The four-character unique ID of the plugin.
+        unique_id: String,
+        /// The start of the name of the plugin in lowercase.
+        name_prefix: String,
+    },
+    /// A VST3 plugin.
+    Vst3,
+    /// A plugin which is bundled with Cubase by Steinberg.
+    BuiltIn,
+}
+
+impl PluginFormat {
+    /// Determines the format of a plugin from its GUID and whether it's bundled with Cubase.
+    ///
+    /// Plugins bundled with Cubase are considered built-in even when they are VST2 plugins, since
+    /// Cubase provides them itself.
+    #[must_use]
+    pub fn from_guid(guid: &str, stock: bool) -> Self {
+        if stock {
+            return Self::BuiltIn;
+        }
+
+        // The GUID of a wrapped VST2 plugin consists of the prefix, the unique ID of the plugin
+        // and the start of its name in lowercase padded with null bytes.
+        match hex::decode(guid) {
+            Ok(guid) if guid.len() == GUID_LENGTH && guid.starts_with(VST2_GUID_PREFIX) => {
+                let (unique_id, name_prefix) =
+                    guid[VST2_GUID_PREFIX.len()..].split_at(VST2_UNIQUE_ID_LENGTH);
+                Self::Vst2 {
+                    unique_id: String::from_utf8_lossy(unique_id).into_owned(),
+                    name_prefix: String::from_utf8_lossy(name_prefix)
+                        .trim_end_matches('\0')
+                        .to_string(),
+                }
+            }
+            _ => Self::Vst3,
+        }
+    }
+
+    /// Determines whether the plugin is a VST2 plugin which isn't bundled with Cubase.
+    #[must_use]
+    pub const fn is_vst2(&self) -> bool {
+        matches!(self, Self::Vst2 { .. })
+    }
+}
+
+impl fmt::Display for PluginFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Self::Vst2 { .. } => "VST2",
+            Self::Vst3 => "VST3",
+            Self::BuiltIn => "built-in",
+        };
+        f.write_str(description)
+    }
+}
+
+/// The kind of track or mixer channel which owns a plugin.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelKind {
+    /// An audio track.
+    AudioTrack,
+    /// A group channel.
+    GroupChannel,
+    /// An instrument loaded in the VST instrument rack.
+    RackInstrument,
+    /// An instrument track.
+    InstrumentTrack,
+    /// An FX channel.
+    FxChannel,
+    /// An input bus.
+    InputBus,
+    /// An output bus.
+    OutputBus,
+    /// A channel type which isn't recognised, along with the type stored in the project file.
+    Unknown(i64),
+}
+
+impl ChannelKind {
+    /// Determines the kind of channel from the type stored in the project file.
+    #[must_use]
+    pub const fn from_type(channel_type: i64) -> Self {
+        match channel_type {
+            1 => Self::AudioTrack,
+            2 => Self::GroupChannel,
+            3 => Self::RackInstrument,
+            4 => Self::InstrumentTrack,
+            6 => Self::FxChannel,
+            7 => Self::InputBus,
+            8 => Self::OutputBus,
+            _ => Self::Unknown(channel_type),
+        }
+    }
+}
+
+impl fmt::Display for ChannelKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Self::AudioTrack => "audio track",
+            Self::GroupChannel => "group channel",
+            Self::RackInstrument => "rack instrument",
+            Self::InstrumentTrack => "instrument track",
+            Self::FxChannel => "FX channel",
+            Self::InputBus => "input bus",
+            Self::OutputBus => "output bus",
+            Self::Unknown(_) => "unknown channel",
+        };
+        f.write_str(description)
+    }
+}
+
+/// Represents a track or mixer channel within a Cubase project.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
+pub struct Channel {
+    /// Name of the track or channel.
+    pub name: String,
+    /// Kind of track or channel.
+    pub kind: ChannelKind,
+}
+
+/// The kind of track within the project window.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackKind {
+    /// An audio track.
+    Audio,
+    /// An instrument track.
+    Instrument,
+    /// A MIDI track.
+    Midi,
+    /// A folder track containing other tracks.
+    Folder,
+    /// A group channel track.
+    Group,
+    /// An FX channel track.
+    Fx,
+    /// A track for an instrument loaded in the VST instrument rack or one of its channels.
+    RackInstrument,
+    /// A track for an input bus.
+    Input,
+    /// A track for an output bus.
+    Output,
+    /// An automation track belonging to another track.
+    Automation,
+    /// The marker track.
+    Marker,
+    /// The tempo track.
+    Tempo,
+    /// The signature track.
+    Signature,
+    /// A track type which isn't recognised.
+    Unknown,
+}
+
+impl TrackKind {
+    /// Determines the kind of track from the class of its object in the project file, returning
+    /// `None` for classes which aren't tracks.
+    #[must_use]
+    pub fn from_class(class: &str) -> Option<Self> {
+        let kind = match class {
+            "MAudioTrackEvent" => Self::Audio,
+            "MInstrumentTrackEvent" => Self::Instrument,
+            "MMidiTrackEvent" => Self::Midi,
+            "MFolderTrack" => Self::Folder,
+            "MAutomationTrackEvent" => Self::Automation,
+            "MMarkerTrackEvent" => Self::Marker,
+            "MTempoTrackEvent" => Self::Tempo,
+            "MSignatureTrackEvent" => Self::Signature,
+            _ if class.ends_with("TrackEvent") => Self::Unknown,
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    /// Determines the kind of track which displays a mixer channel.
+    #[must_use]
+    pub const fn from_channel_kind(channel_kind: ChannelKind) -> Self {
+        match channel_kind {
+            ChannelKind::AudioTrack => Self::Audio,
+            ChannelKind::GroupChannel => Self::Group,
+            ChannelKind::RackInstrument => Self::RackInstrument,
+            ChannelKind::InstrumentTrack => Self::Instrument,
+            ChannelKind::FxChannel => Self::Fx,
+            ChannelKind::InputBus => Self::Input,
+            ChannelKind::OutputBus => Self::Output,
+            ChannelKind::Unknown(_) => Self::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for TrackKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Self::Audio => "audio track",
+            Self::Instrument => "instrument track",
+            Self::Midi => "MIDI track",
+            Self::Folder => "folder track",
+            Self::Group => "group track",
+            Self::Fx => "FX track",
+            Self::RackInstrument => "rack instrument track",
+            Self::Input => "input track",
+            Self::Output => "output track",
+            Self::Automation => "automation track",
+            Self::Marker => "marker track",
+            Self::Tempo => "tempo track",
+            Self::Signature => "signature track",
+            Self::Unknown => "unknown track",
+        };
+        f.write_str(description)
+    }
+}
+
+/// Represents a track within the project window of a Cubase project.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
+pub struct Track {
+    /// Name of the track.
+    pub name: String,
+    /// Kind of track.
+    pub kind: TrackKind,
+    /// Number of folder tracks which contain the track, including the track which an automation
+    /// track belongs to.
+    pub depth: usize,
+}
+
+/// The role which a plugin plays on the track or channel it belongs to.
+#[derive(
+    Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginCategory {
+    /// An instrument on an instrument track or in the VST instrument rack.
+    Instrument,
+    /// An insert effect on a track or channel.
+    Insert,
+    /// An insert effect on an FX channel which is fed by sends.
+    SendEffect,
+    /// A channel strip module such as the EQ or input filter.
+    ChannelStrip,
+    /// A panner of a channel or send.
+    Panner,
+    /// An insert which only processes MIDI.
+    MidiInsert,
+    /// A plugin whose role couldn't be determined.
+    #[default]
+    Unknown,
+}
+
+impl fmt::Display for PluginCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Self::Instrument => "instrument",
+            Self::Insert => "insert effect",
+            Self::SendEffect => "send effect",
+            Self::ChannelStrip => "channel strip module",
+            Self::Panner => "panner",
+            Self::MidiInsert => "MIDI insert",
+            Self::Unknown => "unknown category",
+        };
+        f.write_str(description)
+    }
+}
+
+/// Represents a single use of a plugin within a Cubase project.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PluginInstance {
+    /// The plugin which is used.
+    pub plugin: Plugin,
+    /// The track or channel which the plugin belongs to, if it could be determined.
+    pub channel: Option<Channel>,
+    /// The role which the plugin plays on its track or channel.
+    #[serde(default)]
+    pub category: PluginCategory,
+    /// Byte offset of the plugin within the project file.
+    pub offset: usize,
+    /// Whether the plugin is activated.
+    pub active: bool,
+    /// Whether the plugin belongs to a folder of inserts or channel strip modules which is
+    /// bypassed.
+    pub bypassed: bool,
+    /// The saved state of the plugin, when plugin states are read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<PluginState>,
+}
+
+impl PluginInstance {
+    /// Determines whether the plugin is inserted but bypassed or deactivated.
+    #[must_use]
+    pub const fn is_inactive(&self) -> bool {
+        !self.active || self.bypassed
+    }
+}
+
+/// The saved state of a plugin instance, which may be used to recover its sound or settings.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PluginState {
+    /// State of the audio processor of the plugin.
+    pub component: Vec<u8>,
+    /// State of the edit controller of the plugin, which is often empty.
+    pub controller: Vec<u8>,
+    /// Name of the preset or patch loaded into the plugin, if it could be determined.
+    pub preset_name: Option<String>,
+}
+
+impl PluginState {
+    /// Encodes the state as the contents of a `.vstpreset` file for the plugin with the given
+    /// GUID, which may be loaded into the plugin in Cubase.
+    #[must_use]
+    pub fn to_vst_preset(&self, guid: &str) -> Vec<u8> {
+        let chunks = [(b"Comp", &self.component), (b"Cont", &self.controller)]
+            .into_iter()
+            .filter(|(_, data)| !data.is_empty())
+            .collect::<Vec<_>>();
+
+        // The header consists of the file ID, the format version, the class ID of the plugin and
+        // the offset of the chunk list which follows the chunk data.
+        let mut preset = Vec::new();
+        preset.extend_from_slice(b"VST3");
+        preset.extend_from_slice(&VST_PRESET_VERSION.to_le_bytes());
+        let mut class_id = [b'0'; VST_PRESET_CLASS_ID_LENGTH];
+        for (class_id_byte, guid_byte) in class_id.iter_mut().zip(guid.bytes()) {
+            *class_id_byte = guid_byte;
+        }
+        preset.extend_from_slice(&class_id);
+        let list_offset_index = preset.len();
+        preset.extend_from_slice(&0_u64.to_le_bytes());
+
+        let mut entries = Vec::new();
+        for (id, data) in chunks {
+            entries.push((id, preset.len(), data.len()));
+            preset.extend_from_slice(data);
+        }
+
+        let list_offset = preset.len() as u64;
+        preset[list_offset_index..list_offset_index + 8]
+            .copy_from_slice(&list_offset.to_le_bytes());
+        preset.extend_from_slice(b"List");
+        preset.extend_from_slice(
+            &u32::try_from(entries.len())
+                .unwrap_or(u32::MAX)
+                .to_le_bytes(),
+        );
+        for (id, offset, size) in entries {
+            preset.extend_from_slice(id);
+            preset.extend_from_slice(&(offset as u64).to_le_bytes());
+            preset.extend_from_slice(&(size as u64).to_le_bytes());
+        }
+
+        preset
+    }
+}
+
+/// An audio or video file referenced by the pool of a Cubase project.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
+pub struct MediaFile {
+    /// Path of the file as stored in the project.
+    pub path: String,
+    /// Whether the path is relative to the directory containing the project file.
+    pub relative: bool,
+}
+
 /// Captures the Cubase version and all plugins used for a Cubase project.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Project {
     /// Metadata describing the Cubase version used to create the project.
     pub metadata: Metadata,
-    /// Plugins used in the project.
+    /// Plugins used in the project, without duplicates.
     pub plugins: HashSet<Plugin>,
+    /// Every instance of a plugin in the project along with its owning track or channel, in the
+    /// order they appear in the project file.
+    pub instances: Vec<PluginInstance>,
+    /// Media files referenced by the pool of the project, without duplicates, in the order they
+    /// appear in the project file.
+    #[serde(default)]
+    pub media: Vec<MediaFile>,
+    /// Tracks in the project window, in the order they are displayed with the tracks of each
+    /// folder following the folder itself.
+    #[serde(default)]
+    pub tracks: Vec<Track>,
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    #[rstest]
+    #[case::vst2(
+        "56535473796C3173796C656E74683100",
+        false,
+        PluginFormat::Vst2 { unique_id: "syl1".into(), name_prefix: "sylenth1".into() }
+    )]
+    #[case::vst2_full_name_prefix(
+        "565354416D62726F6D6E697370686572",
+        false,
+        PluginFormat::Vst2 { unique_id: "Ambr".into(), name_prefix: "omnispher".into() }
+    )]
+    #[case::vst3("D39D5B69D6AF42FA1234567868495645", false, PluginFormat::Vst3)]
+    #[case::built_in("946051208E29496E804F64A825C8A047", true, PluginFormat::BuiltIn)]
+    #[case::built_in_vst2("56535455564852757632326872000000", true, PluginFormat::BuiltIn)]
+    #[case::invalid_guid("565354", false, PluginFormat::Vst3)]
+    fn test_plugin_format_from_guid(
+        #[case] guid: &str,
+        #[case] stock: bool,
+        #[case] expected: PluginFormat,
+    ) {
+        assert_eq!(PluginFormat::from_guid(guid, stock), expected);
+    }
+
+    #[rstest]
+    #[case::audio_track(1, ChannelKind::AudioTrack)]
+    #[case::group_channel(2, ChannelKind::GroupChannel)]
+    #[case::rack_instrument(3, ChannelKind::RackInstrument)]
+    #[case::instrument_track(4, ChannelKind::InstrumentTrack)]
+    #[case::fx_channel(6, ChannelKind::FxChannel)]
+    #[case::input_bus(7, ChannelKind::InputBus)]
+    #[case::output_bus(8, ChannelKind::OutputBus)]
+    #[case::unrecognised(42, ChannelKind::Unknown(42))]
+    fn test_channel_kind_from_type(#[case] channel_type: i64, #[case] expected: ChannelKind) {
+        assert_eq!(ChannelKind::from_type(channel_type), expected);
+    }
+
+    #[rstest]
+    #[case::audio("MAudioTrackEvent", Some(TrackKind::Audio))]
+    #[case::instrument("MInstrumentTrackEvent", Some(TrackKind::Instrument))]
+    #[case::midi("MMidiTrackEvent", Some(TrackKind::Midi))]
+    #[case::folder("MFolderTrack", Some(TrackKind::Folder))]
+    #[case::automation("MAutomationTrackEvent", Some(TrackKind::Automation))]
+    #[case::marker("MMarkerTrackEvent", Some(TrackKind::Marker))]
+    #[case::tempo("MTempoTrackEvent", Some(TrackKind::Tempo))]
+    #[case::signature("MSignatureTrackEvent", Some(TrackKind::Signature))]
+    #[case::unrecognised("MChordTrackEvent", Some(TrackKind::Unknown))]
+    #[case::not_track("MTrackList", None)]
+    fn test_track_kind_from_class(#[case] class: &str, #[case] expected: Option<TrackKind>) {
+        assert_eq!(TrackKind::from_class(class), expected);
+    }
+
+    #[test]
+    fn test_plugin_state_to_vst_preset() {
+        let state = PluginState {
+            component: b"component".to_vec(),
+            controller: Vec::new(),
+            preset_name: None,
+        };
+
+        let preset = state.to_vst_preset("565354416D62726F6D6E697370686572");
+
+        let mut expected_preset = Vec::new();
+        expected_preset.extend_from_slice(b"VST3");
+        expected_preset.extend_from_slice(&1_i32.to_le_bytes());
+        expected_preset.extend_from_slice(b"565354416D62726F6D6E697370686572");
+        expected_preset.extend_from_slice(&57_i64.to_le_bytes());
+        expected_preset.extend_from_slice(b"component");
+        expected_preset.extend_from_slice(b"List");
+        expected_preset.extend_from_slice(&1_i32.to_le_bytes());
+        expected_preset.extend_from_slice(b"Comp");
+        expected_preset.extend_from_slice(&48_i64.to_le_bytes());
+        expected_preset.extend_from_slice(&9_i64.to_le_bytes());
+
+        assert_eq!(preset, expected_preset);
+    }
 }
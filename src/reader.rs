@@ -1,21 +1,61 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, mem};
 
 use thiserror::Error;
 
-use crate::project::{Metadata, Plugin, Project};
+use crate::{
+    archive::{Archive, Element, Object},
+    attributes::{self, Attributes, Value},
+    project::{
+        Channel, ChannelKind, MediaFile, Metadata, Plugin, PluginCategory, PluginInstance,
+        PluginState, Project, Tempo, TimeSignature, Track, TrackKind,
+    },
+};
 
-const PLUGIN_UID_SEARCH_TERM: &[u8] = b"Plugin UID\0";
-const APP_VERSION_SEARCH_TERM: &[u8] = b"PAppVersion\0";
+/// The length of the type code and unknown fields which precede the directory of a path.
+const PATH_DIRECTORY_OFFSET: usize = 6;
 
+/// The identifier of each container in the path of a device used by Cubase SX.
+const DEVICE_CONTAINER_ID: &[u8] = b"CONT";
+
+/// The length of the 32-bit float which begins each tempo event stored within a `MTempoTrackEvent`
+/// object, which appears to hold the length of a beat in seconds (0.5 at 120 BPM).
+const TEMPO_EVENT_BEAT_LENGTH: usize = 4;
+
+/// The length of the fields which follow the beat length of each tempo event, such as its position,
+/// which aren't read.
+const TEMPO_EVENT_FIELDS_LENGTH: usize = 18;
+
+/// The length of each tempo event stored within a `MTempoTrackEvent` object.
+const TEMPO_EVENT_LENGTH: usize = TEMPO_EVENT_BEAT_LENGTH + TEMPO_EVENT_FIELDS_LENGTH;
+
+/// The identifier of the header which Cubase adds to the state of VST 2 plugins.
+const VST2_WRAPPER_ID: &[u8] = b"VstW";
+
+/// The length of the header which Cubase adds to the state of VST 2 plugins.
+const VST2_WRAPPER_LENGTH: usize = 16;
+
+/// The identifiers of VST 2 program files whose state includes the name of the program.
+const VST2_PROGRAM_IDS: [&[u8]; 2] = [b"FxCk", b"FPCh"];
+
+/// The offset and maximum length of the program name within a VST 2 program file.
+const VST2_PROGRAM_NAME_OFFSET: usize = 28;
+const VST2_PROGRAM_NAME_LENGTH: usize = 28;
+
+/// The marker preceding the patch name in the state of plugins by u-he, such as Hive.
+const PATCH_NAME_MARKER: &[u8] = b"#pgm=";
+
+/// The tag describing each patch in the state of plugins by Spectrasonics, such as Omnisphere.
+const ENTRY_DESCRIPTION_TAG: &[u8] = b"<ENTRYDESCR";
+
+/// The default patch name used by Spectrasonics plugins for unused parts.
+const DEFAULT_ENTRY_NAME: &str = "Default";
+
+/// An error which occurred while parsing a Cubase project file.
 #[derive(Error, Debug, Eq, PartialEq)]
 pub enum Error {
-    #[error("the length byte goes beyond the end of the project")]
-    LengthBeyondEOF,
-    #[error("the token size goes beyond the end of the project")]
-    TokenBeyondEOF,
     #[error("the project header was unexpected")]
     UnexpectedHeader,
-    #[error("the project has no metadata and appears to be corrupt")]
+    #[error("the project appears to be corrupt")]
     CorruptProject,
     #[error("unable to obtain the application name")]
     NoApplication,
@@ -33,186 +73,899 @@ pub enum Error {
     NoOriginalPluginName,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Format {
-    Riff,
-    Rif2,
+/// The track or mixer channel which plugins found within an attribute list belong to, along with
+/// whether they are bypassed and the role implied by the folder or slot containing them.
+#[derive(Clone, Default)]
+struct Scope {
+    /// The channel which plugins belong to.
+    channel: Option<Channel>,
+    /// Whether plugins belong to a bypassed folder.
+    bypassed: bool,
+    /// The category of plugins implied by the folder or slot containing them.
+    category: Option<PluginCategory>,
 }
 
-/// Determines the used plugins in a Cubase project along with related version of Cubase which the
-/// project was created on by parsing the binary in a *.cpr file.
-pub struct Reader<'a> {
-    /// Binary Cubase project bytes.
-    project_bytes: &'a [u8],
+/// Collects each plugin instance along with the track or mixer channel which owns it by walking
+/// through the attribute lists of a project.
+///
+/// Plugins belong to the channel whose attribute list contains them. Instruments are the
+/// exception, as they are stored alongside an "Output Channel" containing the channel which they
+/// play through. For instrument tracks, this output channel duplicates the track's own channel, so
+/// the plugins within the duplicate are attributed to the track and only kept when the track
+/// doesn't already contain the same instance.
+///
+/// Plugins within a bypassed insert or channel strip folder are marked as bypassed, and each
+/// plugin is marked as deactivated when its "Active" flag is zero.
+///
+/// The category of each plugin is determined by the folder or slot containing it, falling back to
+/// its audio and event port counts where the context doesn't reveal its role.
+#[derive(Default)]
+struct PluginCollector {
+    /// All unique plugins found so far.
+    plugins: HashSet<Plugin>,
+    /// All plugin instances found so far.
+    instances: Vec<PluginInstance>,
+    /// Plugin instances found within output channels which duplicate the channel of their track.
+    duplicate_instances: Vec<PluginInstance>,
+    /// Whether the saved state of each plugin instance is read.
+    read_states: bool,
 }
 
-impl<'a> Reader<'a> {
-    pub const fn new(project_bytes: &'a [u8]) -> Self {
-        Self { project_bytes }
+impl PluginCollector {
+    fn collect_elements(&mut self, elements: &[Element], scope: &Scope) -> Result<(), Error> {
+        for element in elements {
+            match element {
+                Element::Object(object) => self.collect_elements(&object.elements, scope)?,
+                Element::Attributes(attributes) => self.collect_attributes(attributes, scope)?,
+            }
+        }
+        Ok(())
     }
 
-    /// Obtains all project details including Cubase version and plugins used and returns an
-    /// instance of Project containing project details.
-    pub fn get_project_details(&self) -> Result<Project, Error> {
-        let mut metadata = None;
-        let mut plugins = HashSet::new();
+    fn collect_attributes(&mut self, attributes: &Attributes, scope: &Scope) -> Result<(), Error> {
+        let mut scope = scope.clone();
+        if let Some(channel) = read_channel(attributes) {
+            scope.channel = Some(channel);
+            scope.category = None;
+        }
 
-        let format = match self.project_bytes.get(0..4) {
-            Some(b"RIFF") => Format::Riff,
-            Some(b"RIF2") => Format::Rif2,
-            _ => return Err(Error::UnexpectedHeader),
-        };
+        if let Some((plugin, offset)) = read_plugin(attributes)? {
+            self.plugins.insert(plugin.clone());
+            self.instances.push(PluginInstance {
+                plugin,
+                channel: scope.channel.clone(),
+                category: classify_plugin(attributes, &scope),
+                offset,
+                active: attributes
+                    .get_int("Active")
+                    .is_none_or(|active| active != 0),
+                bypassed: scope.bypassed,
+                state: self.read_states.then(|| read_plugin_state(attributes)),
+            });
+        }
+
+        // Plugins stored alongside an output channel are instruments which play through it.
+        let output_channel = read_output_channel(attributes);
+        let mut entry_scope = scope.clone();
+        if output_channel.is_some() {
+            entry_scope.channel.clone_from(&output_channel);
+            entry_scope.category = Some(PluginCategory::Instrument);
+        }
 
-        let mut index = 0;
-        while index < self.project_bytes.len() {
-            // Check if the current byte matches the letter P which is the first letter of all our
-            // search terms.
-            if char::from(self.project_bytes[index]) != 'P' {
-                index += 1;
-                continue;
+        for attribute in &attributes.entries {
+            match (attribute.key.as_ref(), &attribute.value) {
+                ("Output Channels", Value::List(outputs)) => {
+                    // The mixer also stores its output busses under this key, but without
+                    // wrapping each of them in an "Output Channel".
+                    for output in outputs {
+                        let output = output.get_attributes("Output Channel").unwrap_or(output);
+                        self.collect_output_channel(output, &scope, &entry_scope)?;
+                    }
+                }
+                ("Output Channel", Value::Attributes(output)) => {
+                    self.collect_output_channel(output, &scope, &entry_scope)?;
+                }
+                // Only insert and channel strip folders are bypassed as a whole.
+                ("InsertFolder" | "StripFolder", Value::Attributes(folder)) => {
+                    let folder_scope = Scope {
+                        bypassed: folder.get_int("Bypass").is_some_and(|bypass| bypass != 0),
+                        category: folder_category(&attribute.key),
+                        ..entry_scope.clone()
+                    };
+                    self.collect_attributes(folder, &folder_scope)?;
+                }
+                (key, value) => match folder_category(key) {
+                    Some(category) => {
+                        let folder_scope = Scope {
+                            category: Some(category),
+                            ..entry_scope.clone()
+                        };
+                        self.collect_value(value, &folder_scope)?;
+                    }
+                    None => self.collect_value(value, &entry_scope)?,
+                },
             }
+        }
+
+        Ok(())
+    }
 
-            // Check whether the next set of bytes are related to the Cubase version.
-            if metadata.is_none()
-                && let Some((found_metadata, updated_index)) =
-                    self.search_metadata(index, format)?
-            {
-                metadata = Some(found_metadata);
-                index = updated_index;
-                continue;
+    /// Collects the plugins within the output channel of an instrument, setting aside those within
+    /// an output channel which duplicates the channel of the track containing it.
+    fn collect_output_channel(
+        &mut self,
+        output: &Attributes,
+        scope: &Scope,
+        entry_scope: &Scope,
+    ) -> Result<(), Error> {
+        if !is_duplicate_channel(output, scope) {
+            return self.collect_attributes(output, entry_scope);
+        }
+
+        let start = self.instances.len();
+        self.collect_attributes(output, scope)?;
+        let duplicate_instances = self.instances.split_off(start);
+        self.duplicate_instances.extend(duplicate_instances);
+        Ok(())
+    }
+
+    /// Adds the instances within duplicate output channels which aren't already recorded for their
+    /// track, keeping all instances in the order they appear in the project file.
+    fn merge_duplicate_instances(&mut self) {
+        let mut matched = vec![false; self.instances.len()];
+        let mut unmatched_instances = Vec::new();
+        for duplicate in mem::take(&mut self.duplicate_instances) {
+            let index = self
+                .instances
+                .iter()
+                .enumerate()
+                .position(|(index, instance)| {
+                    !matched[index]
+                        && instance.plugin == duplicate.plugin
+                        && instance.channel == duplicate.channel
+                });
+            match index {
+                Some(index) => matched[index] = true,
+                None => unmatched_instances.push(duplicate),
             }
+        }
 
-            // Check whether the next set of bytes relate to a plugin.
-            if let Some((found_plugin, updated_index)) = self.search_plugin(index)? {
-                plugins.insert(found_plugin);
-                index = updated_index;
-                continue;
+        if !unmatched_instances.is_empty() {
+            self.instances.extend(unmatched_instances);
+            self.instances.sort_by_key(|instance| instance.offset);
+        }
+    }
+
+    fn collect_value(&mut self, value: &Value, scope: &Scope) -> Result<(), Error> {
+        match value {
+            Value::Attributes(attributes) => self.collect_attributes(attributes, scope)?,
+            Value::List(items) => {
+                for item in items {
+                    self.collect_attributes(item, scope)?;
+                }
+            }
+            Value::Object(object) => self.collect_attributes(&object.attributes, scope)?,
+            Value::Objects(objects) => {
+                for object in objects {
+                    self.collect_attributes(&object.attributes, scope)?;
+                }
             }
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+/// Determines the category of plugins within the folder or slot stored under a key.
+fn folder_category(key: &str) -> Option<PluginCategory> {
+    let category = match key {
+        "InsertFolder" => PluginCategory::Insert,
+        "StripFolder" | "InputFilter" => PluginCategory::ChannelStrip,
+        "Panner" => PluginCategory::Panner,
+        "Synth Slot" | "Synth Rack" => PluginCategory::Instrument,
+        _ => return None,
+    };
+    Some(category)
+}
+
+/// Determines the category of the plugin described by an attribute list.
+fn classify_plugin(attributes: &Attributes, scope: &Scope) -> PluginCategory {
+    let audio_inputs = attributes.get_int("Audio Input Count");
+    let audio_outputs = attributes.get_int("Audio Output Count");
+    let has_event_inputs = attributes
+        .get_int("Event Input Count")
+        .is_some_and(|count| count > 0);
+
+    // Plugins without any audio ports may only process MIDI, regardless of where they reside.
+    if audio_inputs == Some(0) && audio_outputs == Some(0) && has_event_inputs {
+        return PluginCategory::MidiInsert;
+    }
+
+    let is_fx_channel = scope
+        .channel
+        .as_ref()
+        .is_some_and(|channel| channel.kind == ChannelKind::FxChannel);
 
-            index += 1;
+    match scope.category {
+        Some(PluginCategory::Insert) if is_fx_channel => PluginCategory::SendEffect,
+        Some(category) => category,
+        None if audio_inputs == Some(0) && has_event_inputs => PluginCategory::Instrument,
+        None => PluginCategory::Unknown,
+    }
+}
+
+/// Reads the saved state of the plugin described by an attribute list.
+fn read_plugin_state(attributes: &Attributes) -> PluginState {
+    let component = attributes.get_blob("audioComponent").unwrap_or_default();
+    let controller = attributes.get_blob("editController").unwrap_or_default();
+
+    // The program chosen in the rack of older versions of Cubase takes precedence over the state.
+    let preset_name = attributes
+        .get_str("Bay Program")
+        .filter(|program| !program.is_empty())
+        .map(str::to_string)
+        .or_else(|| read_preset_name(component))
+        .or_else(|| read_preset_name(controller));
+
+    PluginState {
+        component: component.to_vec(),
+        controller: controller.to_vec(),
+        preset_name,
+    }
+}
+
+/// Reads the name of the preset or patch stored within the state of a plugin, when the state is
+/// in a recognised format.
+fn read_preset_name(state: &[u8]) -> Option<String> {
+    read_vst2_program_name(state)
+        .or_else(|| read_patch_name(state))
+        .or_else(|| read_entry_description_name(state))
+}
+
+/// Reads the program name from the state of a VST 2 plugin which saves a single program.
+fn read_vst2_program_name(state: &[u8]) -> Option<String> {
+    let program = state
+        .strip_prefix(VST2_WRAPPER_ID)
+        .and_then(|_| state.get(VST2_WRAPPER_LENGTH..))?;
+    let program_id = program.get(8..12)?;
+    if !VST2_PROGRAM_IDS.contains(&program_id) {
+        return None;
+    }
+
+    let name = program
+        .get(VST2_PROGRAM_NAME_OFFSET..VST2_PROGRAM_NAME_OFFSET + VST2_PROGRAM_NAME_LENGTH)?;
+    let name = name.split(|&byte| byte == 0).next()?;
+    let name = String::from_utf8_lossy(name).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Reads the patch name from a line such as "#pgm=Patch.h2p" in the state of plugins by u-he.
+fn read_patch_name(state: &[u8]) -> Option<String> {
+    let index = find_bytes(state, PATCH_NAME_MARKER)? + PATCH_NAME_MARKER.len();
+    let line = state[index..]
+        .split(|&byte| matches!(byte, b'\n' | b'\r' | 0))
+        .next()?;
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim();
+    let name = line
+        .rsplit_once('.')
+        .filter(|(_, extension)| extension.bytes().all(|byte| byte.is_ascii_alphanumeric()))
+        .map_or(line, |(name, _)| name);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Reads the patch name from the `<ENTRYDESCR>` tags in the state of plugins by Spectrasonics,
+/// preferring the first name which isn't the default used for unused parts.
+fn read_entry_description_name(state: &[u8]) -> Option<String> {
+    let mut names = Vec::new();
+    let mut index = 0;
+    while let Some(tag_index) = find_bytes(&state[index..], ENTRY_DESCRIPTION_TAG) {
+        let tag_start = index + tag_index;
+        let tag_length = state[tag_start..]
+            .iter()
+            .position(|&byte| byte == b'>')
+            .unwrap_or(state.len() - tag_start);
+        let tag = String::from_utf8_lossy(&state[tag_start..tag_start + tag_length]);
+        if let Some((_, name)) = tag.split_once(" name=\"")
+            && let Some((name, _)) = name.split_once('"')
+        {
+            names.push(unescape_xml(name));
         }
+        index = tag_start + tag_length;
+    }
+
+    let preferred_name = names.iter().position(|name| name != DEFAULT_ENTRY_NAME);
+    let name_index = preferred_name.unwrap_or(0);
+    (name_index < names.len()).then(|| names.swap_remove(name_index))
+}
+
+/// Replaces the predefined entities of an XML attribute value with the characters they represent.
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Finds the index of the first occurrence of a sequence of bytes.
+fn find_bytes(bytes: &[u8], needle: &[u8]) -> Option<usize> {
+    bytes
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Reads the channel described by an attribute list, which consists of a name followed by the
+/// type of channel.
+fn read_channel(attributes: &Attributes) -> Option<Channel> {
+    let name_position = attributes.position("Name")?;
+    let name = attributes.entries[name_position].value.as_attributes()?;
+    let [name_attribute] = name.entries.as_slice() else {
+        return None;
+    };
+    if name_attribute.key != "String" {
+        return None;
+    }
+    let name = name_attribute.value.as_str()?;
 
-        metadata.map_or_else(
-            || Err(Error::CorruptProject),
-            |metadata| Ok(Project { metadata, plugins }),
-        )
+    // Other objects also store their name in this way, so only those followed by a channel type
+    // are considered to be channels.
+    let type_attribute = attributes.entries.get(name_position + 1)?;
+    if type_attribute.key != "Type" {
+        return None;
     }
+    let channel_type = type_attribute.value.as_int()?;
 
-    fn search_metadata(
-        &self,
-        index: usize,
-        format: Format,
-    ) -> Result<Option<(Metadata, usize)>, Error> {
-        let mut index = index;
+    Some(Channel {
+        name: name.to_string(),
+        kind: ChannelKind::from_type(channel_type),
+    })
+}
+
+/// Reads the channel which the instrument in an attribute list plays through.
+fn read_output_channel(attributes: &Attributes) -> Option<Channel> {
+    let output = match attributes.get("Output Channels") {
+        Some(Value::List(outputs)) => outputs.first()?.get_attributes("Output Channel")?,
+        _ => attributes.get_attributes("Output Channel")?,
+    };
+    read_channel(output)
+}
+
+/// Determines whether an output channel duplicates the channel which contains it.
+fn is_duplicate_channel(output: &Attributes, scope: &Scope) -> bool {
+    read_channel(output).is_some_and(|channel| scope.channel.as_ref() == Some(&channel))
+}
+
+/// Reads the plugin described by an attribute list along with the offset of its "Plugin UID" key.
+fn read_plugin(attributes: &Attributes) -> Result<Option<(Plugin, usize)>, Error> {
+    let Some(uid_position) = attributes.position("Plugin UID") else {
+        return Ok(None);
+    };
+    let uid_attribute = &attributes.entries[uid_position];
+
+    let guid = uid_attribute
+        .value
+        .as_attributes()
+        .and_then(|uid| uid.get_str("GUID"))
+        .ok_or(Error::NoPluginGUID)?;
+
+    let name_position = attributes
+        .position("Plugin Name")
+        .ok_or(Error::NoPluginName)?;
+    let mut name = attributes.entries[name_position]
+        .value
+        .as_str()
+        .ok_or(Error::NoPluginName)?;
+
+    if attributes.truncated && name_position == attributes.entries.len() - 1 {
+        return Err(Error::NoTokenAfterPluginName);
+    }
+
+    // In Cubase 8.x and above, in cases where an instrument track has been renamed using
+    // Shift+Enter, the name retrieved above will be the track title and the name of the plugin
+    // will follow under the key "Original Plugin Name".
+    match attributes.get("Original Plugin Name") {
+        Some(Value::String(original_name)) => name = original_name,
+        Some(_) => return Err(Error::NoOriginalPluginName),
+        None => (),
+    }
+
+    Ok(Some((
+        Plugin {
+            guid: guid.to_string(),
+            name: name.to_string(),
+        },
+        uid_attribute.offset,
+    )))
+}
+
+/// Reads the Cubase version which created the project from the fields of a `PAppVersion` object
+/// along with the main settings of the project.
+fn read_metadata(app_version: &Object, archive: &Archive) -> Result<Metadata, Error> {
+    let payload = app_version.payload;
+
+    let (application, index) = attributes::read_string(payload, 0).ok_or(Error::NoApplication)?;
+    let (version, index) = attributes::read_string(payload, index).ok_or(Error::NoVersion)?;
+    let (release_date, index) =
+        attributes::read_string(payload, index).ok_or(Error::NoReleaseDate)?;
+
+    let version = version.strip_prefix("Version ").unwrap_or(&version);
+
+    // Older 32-bit versions of Cubase didn't list the architecture in the project file.
+    let architecture = attributes::read_string(payload, index + 4)
+        .map_or_else(|| "Unspecified".into(), |(architecture, _)| architecture);
+
+    let tempo_track = archive
+        .objects()
+        .find(|object| object.class == "MTempoTrackEvent")
+        .and_then(read_tempo_track);
+    let signature_track = archive
+        .objects()
+        .find(|object| object.class == "MSignatureTrackEvent");
+
+    // Cubase 5 and above also store the tempo, time signature and audio format of the project as
+    // media attributes.
+    let tempo = read_media_attribute(archive, "MusicalTempo")
+        .and_then(|attribute| attribute.get("Float"))
+        .and_then(Value::as_float)
+        .or_else(|| tempo_track.map(|(tempo, _)| tempo))
+        .map(|bpm| Tempo { bpm });
+    let time_signature = signature_track.and_then(read_time_signature).or_else(|| {
+        read_media_attribute(archive, "MusicalSignature")
+            .and_then(|attribute| match attribute.get("Object") {
+                Some(Value::Object(signature)) => Some(&signature.attributes),
+                _ => None,
+            })
+            .and_then(read_time_signature_attributes)
+    });
+    let sample_rate = read_media_attribute(archive, "AudioSampleRate")
+        .and_then(|attribute| attribute.get("Float"))
+        .and_then(Value::as_float)
+        .and_then(sample_rate_hz);
+    let bit_depth = read_media_attribute(archive, "AudioSampleSize")
+        .and_then(|attribute| attribute.get_int("Long"))
+        .and_then(|bit_depth| u32::try_from(bit_depth).ok());
+
+    Ok(Metadata {
+        application: application.into_owned(),
+        version: version.to_string(),
+        release_date: release_date.into_owned(),
+        architecture: architecture.into_owned(),
+        tempo,
+        tempo_track_active: tempo_track.map(|(_, active)| active),
+        time_signature,
+        sample_rate,
+        bit_depth,
+    })
+}
+
+/// Converts a sample rate stored as a float into whole hertz, which sample rates are always given
+/// in.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn sample_rate_hz(sample_rate: f64) -> Option<u32> {
+    (1.0..=f64::from(u32::MAX))
+        .contains(&sample_rate)
+        .then(|| sample_rate.round() as u32)
+}
 
-        match self.get_bytes(index, APP_VERSION_SEARCH_TERM.len()) {
-            Some(APP_VERSION_SEARCH_TERM) => (),
-            _ => return Ok(None),
+/// Obtains the attributes of the media attribute with the given ID, which hold a single value
+/// under a key named after its type (such as "Float").
+fn read_media_attribute<'b, 'a>(archive: &'b Archive<'a>, id: &str) -> Option<&'b Attributes<'a>> {
+    archive
+        .objects()
+        .filter(|object| object.class == "StMedia::PAttributes")
+        .flat_map(Object::attributes)
+        .filter_map(|attributes| match attributes.get("PAttr") {
+            Some(Value::Objects(media_attributes)) => Some(media_attributes),
+            _ => None,
+        })
+        .flatten()
+        .map(|media_attribute| &media_attribute.attributes)
+        .find(|attributes| attributes.get_str("ID") == Some(id))
+}
+
+/// Reads the fixed tempo of the project and whether the tempo track is active from the fields of
+/// a `MTempoTrackEvent` object.
+///
+/// The fields begin with the number of tempo events followed by the events themselves, the fixed
+/// tempo in beats per minute as a 32-bit float and then the state of the tempo track as a 16-bit
+/// flag.
+fn read_tempo_track(tempo_track: &Object) -> Option<(f64, bool)> {
+    let payload = tempo_track.payload;
+
+    let event_count = usize::try_from(attributes::read_u32(payload, 0)?).ok()?;
+    let index = 4 + event_count.checked_mul(TEMPO_EVENT_LENGTH)?;
+
+    let tempo = f32::from_be_bytes(attributes::read_array(payload, index)?);
+    let active = u16::from_be_bytes(attributes::read_array(payload, index + 4)?);
+    Some((f64::from(tempo), active != 0))
+}
+
+/// Reads the initial time signature of the project from a `MSignatureTrackEvent` object.
+///
+/// Cubase 11 and above store each time signature as a `MTimeSignatureEvent` attribute list, while
+/// the fields of the object begin with the number of time signatures followed by the bar,
+/// numerator and denominator of the first.
+fn read_time_signature(signature_track: &Object) -> Option<TimeSignature> {
+    let first_event = signature_track
+        .objects()
+        .flat_map(Object::attributes)
+        .find_map(|attributes| match attributes.get("SignatureEvent") {
+            Some(Value::Objects(events)) => events.first(),
+            _ => None,
+        });
+    if let Some(first_event) = first_event {
+        return read_time_signature_attributes(&first_event.attributes);
+    }
+
+    let payload = signature_track.payload;
+    if attributes::read_u32(payload, 0)? == 0 {
+        return None;
+    }
+
+    Some(TimeSignature {
+        numerator: u16::from_be_bytes(attributes::read_array(payload, 8)?).into(),
+        denominator: u16::from_be_bytes(attributes::read_array(payload, 10)?).into(),
+    })
+}
+
+/// Reads a time signature from an attribute list containing its numerator and denominator.
+fn read_time_signature_attributes(attributes: &Attributes) -> Option<TimeSignature> {
+    Some(TimeSignature {
+        numerator: u32::try_from(attributes.get_int("Numerator")?).ok()?,
+        denominator: u32::try_from(attributes.get_int("Denominator")?).ok()?,
+    })
+}
+
+/// Reads the tracks of the project from the root of the arrangement, which contains the track list
+/// followed by the marker track.
+fn read_tracks(archive: &Archive) -> Vec<Track> {
+    let mut tracks = Vec::new();
+    if let Some(root) = archive.objects().find(|object| {
+        object.class == "MRoot" && object.children().any(|child| child.class == "MTrackList")
+    }) {
+        for track_list in root.children().filter(|child| child.class == "MTrackList") {
+            collect_tracks(track_list, 0, &mut tracks);
+        }
+        collect_tracks(root, 0, &mut tracks);
+    }
+    tracks
+}
+
+/// Adds each track within a track list along with the tracks of any folders it contains.
+///
+/// Each track begins with a node whose fields begin with the name of the track. For folder
+/// tracks, this node is the track list containing the tracks of the folder, while for other
+/// tracks it may also contain the tempo and signature tracks, which are listed before the track. The
+/// automation tracks of a track
+/// are stored in a separate automation node and are listed beneath the track.
+fn collect_tracks(track_list: &Object, depth: usize, tracks: &mut Vec<Track>) {
+    for track in track_list.children() {
+        let kind = if track.class == "MDeviceTrackEvent" {
+            read_device_track_kind(track)
+        } else if let Some(kind) = TrackKind::from_class(&track.class) {
+            kind
+        } else {
+            continue;
+        };
+
+        // Tempo, signature and automation tracks don't begin with a node containing a name.
+        let node = track
+            .children()
+            .next()
+            .filter(|node| node.class == "MListNode" || node.class == "MTrackList");
+        let name = node
+            .and_then(|node| attributes::read_string(node.payload, 0))
+            .map(|(name, _)| name.into_owned())
+            .unwrap_or_default();
+        if kind == TrackKind::Folder {
+            tracks.push(Track { name, kind, depth });
+            if let Some(folder_track_list) = node {
+                collect_tracks(folder_track_list, depth + 1, tracks);
+            }
+        } else {
+            if let Some(node) = node {
+                collect_tracks(node, depth, tracks);
+            }
+            tracks.push(Track { name, kind, depth });
         }
-        index += APP_VERSION_SEARCH_TERM.len() + 9;
-        if format == Format::Rif2 {
-            index += 4;
+
+        for automation_node in track
+            .children()
+            .filter(|object| object.class == "MAutomationNode")
+        {
+            collect_tracks(automation_node, depth + 1, tracks);
         }
+    }
+}
 
-        let (application, len) = self.get_token(index).map_err(|_| Error::NoApplication)?;
-        index += len + 3;
+/// Determines the kind of a track which displays a mixer channel or device.
+///
+/// The channel is stored within the track for group and FX channels, while other tracks only
+/// store the path of their device within the mixer (e.g. `VST Mixer\Channels\OutputChannel`).
+fn read_device_track_kind(track: &Object) -> TrackKind {
+    if let Some(channel) = track
+        .objects()
+        .flat_map(Object::attributes)
+        .find_map(find_channel)
+    {
+        return TrackKind::from_channel_kind(channel.kind);
+    }
 
-        let (version, len) = self.get_token(index).map_err(|_| Error::NoVersion)?;
-        index += len + 3;
+    let device_path = track
+        .children()
+        .find(|object| object.class == "MTrack")
+        .map(read_device_path)
+        .unwrap_or_default();
+    match device_path
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [_, "VST Instruments", ..] => TrackKind::RackInstrument,
+        [_, "Channels", channel] if channel.starts_with("Synth") => TrackKind::RackInstrument,
+        [_, "Channels", "InputChannel"] => TrackKind::Input,
+        [_, "Channels", "OutputChannel"] => TrackKind::Output,
+        _ => TrackKind::Unknown,
+    }
+}
 
-        let version = version
-            .strip_prefix("Version ")
-            .map(ToString::to_string)
-            .unwrap_or(version);
+/// Reads the path of the device which a track displays from the fields of its `MTrack` object.
+///
+/// Cubase SX stored each part of the path in a separate container beginning with "CONT", starting
+/// with the root of all devices. Later versions store a single path separated by backslashes.
+fn read_device_path(device_track: &Object) -> Vec<String> {
+    let payload = device_track.payload;
 
-        let (release_date, len) = self.get_token(index).map_err(|_| Error::NoReleaseDate)?;
-        index += len + 7;
+    let mut index = payload
+        .windows(DEVICE_CONTAINER_ID.len())
+        .position(|window| window == DEVICE_CONTAINER_ID);
+    if index.is_none() {
+        return attributes::read_string(payload, 2)
+            .map(|(device_path, _)| device_path.split('\\').map(str::to_string).collect())
+            .unwrap_or_default();
+    }
 
-        // Older 32-bit versions of Cubase didn't list the architecture in the project file.
-        let architecture = match self.get_token(index) {
-            Ok((architecture, len)) => {
-                index += len;
-                architecture
-            }
-            Err(_) => String::from("Unspecified"),
+    let mut device_path = Vec::new();
+    while let Some(container_index) =
+        index.filter(|&index| payload.get(index..index + 4) == Some(DEVICE_CONTAINER_ID))
+    {
+        let Some((part, next_index)) = attributes::read_string(payload, container_index + 4) else {
+            break;
         };
+        device_path.push(part.into_owned());
+        index = Some(next_index);
+    }
 
-        Ok(Some((
-            Metadata {
-                application,
-                version,
-                release_date,
-                architecture,
-            },
-            index,
-        )))
+    // Skip the root of all devices so that paths match those of later versions.
+    if device_path
+        .first()
+        .is_some_and(|part| part == "Device Root")
+    {
+        device_path.remove(0);
     }
+    device_path
+}
+
+/// Finds the first channel described within an attribute list or those nested within it.
+fn find_channel(attributes: &Attributes) -> Option<Channel> {
+    read_channel(attributes).or_else(|| {
+        attributes
+            .entries
+            .iter()
+            .find_map(|entry| match &entry.value {
+                Value::Attributes(attributes) => find_channel(attributes),
+                Value::List(items) => items.iter().find_map(find_channel),
+                Value::Object(object) => find_channel(&object.attributes),
+                Value::Objects(objects) => objects
+                    .iter()
+                    .find_map(|object| find_channel(&object.attributes)),
+                _ => None,
+            })
+    })
+}
 
-    fn search_plugin(&self, index: usize) -> Result<Option<(Plugin, usize)>, Error> {
-        let mut index = index;
+/// Reads the media files referenced by the pool of the project, which are stored as `FNPath`
+/// objects or attribute lists containing a `NodePath`.
+fn read_media_files(archive: &Archive) -> Vec<MediaFile> {
+    let mut media_files = Vec::new();
 
-        match self.get_bytes(index, PLUGIN_UID_SEARCH_TERM.len()) {
-            Some(PLUGIN_UID_SEARCH_TERM) => (),
-            _ => return Ok(None),
+    for pool in archive.objects().filter(|object| object.class == "PPool") {
+        for attributes in pool.attributes() {
+            collect_node_paths(attributes, &mut media_files);
         }
-        index += PLUGIN_UID_SEARCH_TERM.len() + 22;
 
-        let (guid, len) = self.get_token(index).map_err(|_| Error::NoPluginGUID)?;
-        index += len + 3;
+        // Paths directly within the pool describe the location of the project itself, so only
+        // those nested within the entries of the pool refer to media.
+        for entry in pool.children() {
+            for attributes in entry.attributes() {
+                collect_node_paths(attributes, &mut media_files);
+            }
+
+            for object in entry.objects() {
+                if let Some(path) = read_path(object) {
+                    add_media_file(
+                        &mut media_files,
+                        MediaFile {
+                            relative: !is_absolute_path(&path),
+                            path,
+                        },
+                    );
+                }
 
-        let (key, len) = self.get_token(index).map_err(|_| Error::NoPluginName)?;
-        if key != "Plugin Name" {
-            return Err(Error::NoPluginName);
+                for attributes in object.attributes() {
+                    collect_node_paths(attributes, &mut media_files);
+                }
+            }
         }
-        index += len + 5;
+    }
+
+    media_files
+}
 
-        let (mut name, len) = self.get_token(index).map_err(|_| Error::NoPluginName)?;
-        index += len + 3;
+/// Reads the path stored in the fields of a `FNPath` or `FPath` object.
+///
+/// The fields of a `FNPath` begin with the file name followed by its type code, extensions and
+/// description, while the directory is stored last as the only field of a `FPath`.
+fn read_path(path: &Object) -> Option<String> {
+    match path.class.as_ref() {
+        "FNPath" => {
+            let payload = path.payload;
+            let (name, index) = attributes::read_string(payload, 0)?;
+            let (_, index) = attributes::read_string(payload, index + 4)?;
+            let (_, index) = attributes::read_string(payload, index)?;
+            let (_, index) = attributes::read_string(payload, index)?;
+            let (directory, _) = attributes::read_string(payload, index + PATH_DIRECTORY_OFFSET)?;
+            (!name.is_empty()).then(|| format!("{directory}{name}"))
+        }
+        "FPath" => {
+            let (directory, _) = attributes::read_string(path.payload, PATH_DIRECTORY_OFFSET)?;
+            (!directory.is_empty()).then(|| directory.into_owned())
+        }
+        _ => None,
+    }
+}
 
-        // In Cubase 8.x and above, in cases where an instrument track has been renamed using
-        // Shift+Enter, the name retrieved above will be the track title and the name of the plugin
-        // will follow under the key "Original Plugin Name".
-        let (key, len) = self
-            .get_token(index)
-            .map_err(|_| Error::NoTokenAfterPluginName)?;
-        if key == "Original Plugin Name" {
-            index += len + 5;
+/// Adds the media files of any attribute lists containing a `NodePath` and `IsRelativePath`.
+fn collect_node_paths(attributes: &Attributes, media_files: &mut Vec<MediaFile>) {
+    if let Some(path) = attributes
+        .get_str("NodePath")
+        .filter(|path| !path.is_empty())
+    {
+        add_media_file(
+            media_files,
+            MediaFile {
+                path: path.to_string(),
+                relative: attributes
+                    .get_int("IsRelativePath")
+                    .is_some_and(|relative| relative != 0),
+            },
+        );
+    }
 
-            let (original_name, len) = self
-                .get_token(index)
-                .map_err(|_| Error::NoOriginalPluginName)?;
-            name = original_name;
-            index += len;
+    for entry in &attributes.entries {
+        match &entry.value {
+            Value::Attributes(attributes) => collect_node_paths(attributes, media_files),
+            Value::List(items) => {
+                for attributes in items {
+                    collect_node_paths(attributes, media_files);
+                }
+            }
+            Value::Object(object) => collect_node_paths(&object.attributes, media_files),
+            Value::Objects(objects) => {
+                for object in objects {
+                    collect_node_paths(&object.attributes, media_files);
+                }
+            }
+            _ => {}
         }
+    }
+}
 
-        Ok(Some((Plugin { guid, name }, index)))
+/// Adds a media file unless it has already been added.
+fn add_media_file(media_files: &mut Vec<MediaFile>, media_file: MediaFile) {
+    if !media_files.contains(&media_file) {
+        media_files.push(media_file);
     }
+}
+
+/// Determines whether a path stored in a project is absolute on either Windows or macOS.
+fn is_absolute_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with(['/', '\\'])
+        || bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+/// Determines the used plugins in a Cubase project along with related version of Cubase which the
+/// project was created on by parsing the binary in a *.cpr file.
+pub struct Reader<'a> {
+    /// Binary Cubase project bytes.
+    project_bytes: &'a [u8],
+    /// Whether the saved state of each plugin instance is read.
+    read_states: bool,
+}
 
-    fn get_bytes(&self, index: usize, len: usize) -> Option<&[u8]> {
-        let end = index + len;
-        if end > self.project_bytes.len() {
-            return None;
+impl<'a> Reader<'a> {
+    /// Creates a reader for the bytes of a Cubase project file.
+    #[must_use]
+    pub const fn new(project_bytes: &'a [u8]) -> Self {
+        Self {
+            project_bytes,
+            read_states: false,
         }
+    }
 
-        Some(&self.project_bytes[index..end])
+    /// Also reads the saved state of each plugin instance along with the name of its preset,
+    /// which may use a lot of memory for plugins such as samplers.
+    #[must_use]
+    pub const fn with_plugin_states(mut self) -> Self {
+        self.read_states = true;
+        self
     }
 
-    fn get_token(&self, index: usize) -> Result<(String, usize), Error> {
-        let len_bytes = self.get_bytes(index, 1).ok_or(Error::LengthBeyondEOF)?;
-        let len = usize::from(len_bytes[0]);
+    /// Obtains all project details including Cubase version and plugins used and returns an
+    /// instance of Project containing project details.
+    ///
+    /// ```
+    /// use std::fs;
+    ///
+    /// use cubase_project_plugins::{Plugin, Reader};
+    ///
+    /// let project_bytes = fs::read("testdata/Example Project (Cubase 4.5 64-bit).cpr")?;
+    /// let project = Reader::new(&project_bytes).get_project_details()?;
+    ///
+    /// assert_eq!(project.metadata.version, "4.5.2");
+    /// assert_eq!(project.metadata.architecture, "WIN64");
+    /// assert!(project.plugins.contains(&Plugin {
+    ///     guid: "56535473796C3173796C656E74683100".to_string(),
+    ///     name: "Sylenth1".to_string(),
+    /// }));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the project header is unexpected, if the project contains no metadata
+    /// or if the metadata or a plugin is truncated.
+    ///
+    /// ```
+    /// use std::fs;
+    ///
+    /// use cubase_project_plugins::{Reader, reader::Error};
+    ///
+    /// let project_bytes = fs::read("testdata/Truncated Project (Plugin GUID).cpr")?;
+    /// let result = Reader::new(&project_bytes).get_project_details();
+    ///
+    /// assert_eq!(result, Err(Error::NoPluginGUID));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn get_project_details(&self) -> Result<Project, Error> {
+        let archive = Archive::parse(self.project_bytes)?;
 
-        let token_bytes = self
-            .get_bytes(index + 1, len)
-            .ok_or(Error::TokenBeyondEOF)?;
+        let app_version = archive
+            .objects()
+            .find(|object| object.class == "PAppVersion")
+            .ok_or(Error::CorruptProject)?;
+        let metadata = read_metadata(app_version, &archive)?;
 
-        // Older versions of before Cubase 5 didn't always provide nul terminators in token strings.
-        let nul_index = token_bytes.iter().position(|&byte| byte == 0);
-        let token = nul_index.map_or_else(
-            || String::from_utf8_lossy(token_bytes),
-            |nul_index| String::from_utf8_lossy(&token_bytes[..nul_index]),
-        );
+        let mut collector = PluginCollector {
+            read_states: self.read_states,
+            ..PluginCollector::default()
+        };
+        for section in &archive.sections {
+            collector.collect_elements(&section.elements, &Scope::default())?;
+        }
+        collector.merge_duplicate_instances();
 
-        Ok((token.to_string(), len + 1))
+        Ok(Project {
+            metadata,
+            plugins: collector.plugins,
+            instances: collector.instances,
+            media: read_media_files(&archive),
+            tracks: read_tracks(&archive),
+        })
     }
 }
 
@@ -225,6 +978,10 @@ mod tests {
     use similar_asserts::assert_eq;
 
     use super::*;
+    use crate::attributes::Attribute;
+
+    const PLUGIN_UID_KEY: &[u8] = b"Plugin UID\0";
+    const BYPASS_ATTRIBUTE: &[u8] = b"\0\0\0\x07Bypass\0\0\x01";
 
     struct PluginProperties {
         pub includes_channel_plugins: bool,
@@ -248,6 +1005,14 @@ fn default() -> Self {
             version: "4.5.2".to_string(),
             release_date: "Sep  2 2008".to_string(),
             architecture: "WIN32".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: None,
+            bit_depth: None,
         },
         PluginProperties::default(),
     )]
@@ -258,6 +1023,14 @@ fn default() -> Self {
             version: "4.5.2".to_string(),
             release_date: "Sep  2 2008".to_string(),
             architecture: "WIN64".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: None,
+            bit_depth: None,
         },
         PluginProperties::default(),
     )]
@@ -268,6 +1041,14 @@ fn default() -> Self {
             version: "5.5.3".to_string(),
             release_date: "Jan 13 2011".to_string(),
             architecture: "WIN32".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: Some(44_100),
+            bit_depth: Some(16),
         },
         PluginProperties::default(),
     )]
@@ -278,6 +1059,14 @@ fn default() -> Self {
             version: "5.5.3".to_string(),
             release_date: "Jan 13 2011".to_string(),
             architecture: "WIN64".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: Some(44_100),
+            bit_depth: Some(16),
         },
         PluginProperties::default(),
     )]
@@ -288,6 +1077,14 @@ fn default() -> Self {
             version: "6.5.5".to_string(),
             release_date: "Jun 24 2013".to_string(),
             architecture: "WIN32".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: Some(44_100),
+            bit_depth: Some(16),
         },
         PluginProperties::default(),
     )]
@@ -298,6 +1095,14 @@ fn default() -> Self {
             version: "6.5.5".to_string(),
             release_date: "Jun 24 2013".to_string(),
             architecture: "WIN64".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: Some(44_100),
+            bit_depth: Some(16),
         },
         PluginProperties::default(),
     )]
@@ -308,6 +1113,14 @@ fn default() -> Self {
             version: "7.0.7".to_string(),
             release_date: "Jan 21 2014".to_string(),
             architecture: "WIN32".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: Some(44_100),
+            bit_depth: Some(16),
         },
         PluginProperties {
             includes_channel_plugins: true,
@@ -321,6 +1134,14 @@ fn default() -> Self {
             version: "7.0.7".to_string(),
             release_date: "Jan 21 2014".to_string(),
             architecture: "WIN64".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: Some(44_100),
+            bit_depth: Some(16),
         },
         PluginProperties {
             includes_channel_plugins: true,
@@ -334,6 +1155,14 @@ fn default() -> Self {
             version: "8.5.30".to_string(),
             release_date: "Feb 22 2017".to_string(),
             architecture: "WIN32".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: Some(44_100),
+            bit_depth: Some(16),
         },
         PluginProperties {
             includes_channel_plugins: true,
@@ -347,6 +1176,14 @@ fn default() -> Self {
             version: "8.5.30".to_string(),
             release_date: "Feb 22 2017".to_string(),
             architecture: "WIN64".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: Some(44_100),
+            bit_depth: Some(16),
         },
         PluginProperties {
             includes_channel_plugins: true,
@@ -360,6 +1197,14 @@ fn default() -> Self {
             version: "9.5.50".to_string(),
             release_date: "Feb  2 2019".to_string(),
             architecture: "WIN64".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: Some(44_100),
+            bit_depth: Some(16),
         },
         PluginProperties {
             includes_channel_plugins: true,
@@ -373,6 +1218,14 @@ fn default() -> Self {
             version: "11.0.41".to_string(),
             release_date: "Sep 27 2021".to_string(),
             architecture: "WIN64".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: Some(44_100),
+            bit_depth: Some(16),
         },
         PluginProperties {
             includes_channel_plugins: true,
@@ -386,6 +1239,14 @@ fn default() -> Self {
             version: "13.0.10".to_string(),
             release_date: "Oct 10 2023".to_string(),
             architecture: "WIN64".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: Some(44_100),
+            bit_depth: Some(16),
         },
         PluginProperties {
             includes_channel_plugins: true,
@@ -399,6 +1260,14 @@ fn default() -> Self {
             version: "14.0.30".to_string(),
             release_date: "May 22 2025".to_string(),
             architecture: "WIN64".to_string(),
+            tempo: Some(Tempo { bpm: 120.0 }),
+            tempo_track_active: Some(false),
+            time_signature: Some(TimeSignature {
+                numerator: 4,
+                denominator: 4,
+            }),
+            sample_rate: Some(44_100),
+            bit_depth: Some(16),
         },
         PluginProperties {
             includes_channel_plugins: true,
@@ -417,7 +1286,7 @@ fn test_get_project_details(
         let project_details = reader.get_project_details().unwrap();
 
         let mut actual_plugins_sorted = Vec::from_iter(project_details.plugins);
-        actual_plugins_sorted.sort_by(|a, b| a.guid.to_lowercase().cmp(&b.guid.to_lowercase()));
+        actual_plugins_sorted.sort_by_key(|plugin| plugin.guid.to_lowercase());
 
         let mut expected_plugins = vec![Plugin {
             guid: "1C3A662167D347A99F7D797EA4911CDB".to_string(),
@@ -481,6 +1350,355 @@ fn test_get_project_details(
         assert_eq!(actual_plugins_sorted, expected_plugins);
     }
 
+    #[rstest]
+    #[case::cubase_45_32_bit("Example Project (Cubase 4.5 32-bit).cpr", "UV22HR")]
+    #[case::cubase_5_64_bit("Example Project (Cubase 5 64-bit).cpr", "UV22HR")]
+    #[case::cubase_65_32_bit("Example Project (Cubase 6.5 32-bit).cpr", "UV22HR")]
+    #[case::cubase_7_64_bit("Example Project (Cubase 7 64-bit).cpr", "UV22HR")]
+    #[case::cubase_85_32_bit("Example Project (Cubase 8.5 32-bit).cpr", "UV22HR")]
+    #[case::cubase_95("Example Project (Cubase 9.5).cpr", "UV22HR")]
+    #[case::cubase_11("Example Project (Cubase 11).cpr", "UV22HR")]
+    #[case::cubase_14("Example Project (Cubase 14).cpr", "Lin Dither")]
+    fn test_get_project_details_instances(
+        #[case] filename: &str,
+        #[case] dither_plugin_name: &str,
+    ) {
+        let project_path = PathBuf::from("testdata").join(filename);
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let reader = Reader::new(&project_bytes);
+        let project_details = reader.get_project_details().unwrap();
+
+        // Channel strip plugins are present on every channel in newer versions of Cubase, so
+        // they are excluded to allow all versions to be compared.
+        let actual_instances = project_details
+            .instances
+            .iter()
+            .filter(|instance| {
+                !["Standard Panner", "EQ", "Input Filter"].contains(&instance.plugin.name.as_str())
+            })
+            .map(|instance| {
+                let channel = instance.channel.as_ref().unwrap();
+                (
+                    instance.plugin.name.as_str(),
+                    channel.name.as_str(),
+                    channel.kind,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let expected_instances = vec![
+            ("StudioEQ", "Acid Bass", ChannelKind::InstrumentTrack),
+            ("Sylenth1", "Acid Bass", ChannelKind::InstrumentTrack),
+            ("TrackComp", "Solo Vocalist", ChannelKind::InstrumentTrack),
+            (
+                "StereoEnhancer",
+                "Solo Vocalist",
+                ChannelKind::InstrumentTrack,
+            ),
+            ("Omnisphere", "Solo Vocalist", ChannelKind::InstrumentTrack),
+            ("ArtsAcousticReverb", "FX 1-Reverb", ChannelKind::FxChannel),
+            ("Elephant", "Stereo Out", ChannelKind::OutputBus),
+            (dither_plugin_name, "Stereo Out", ChannelKind::OutputBus),
+            ("Hive", "Hive", ChannelKind::RackInstrument),
+        ];
+
+        assert_eq!(actual_instances, expected_instances);
+    }
+
+    #[rstest]
+    #[case::cubase_45_32_bit("Example Project (Cubase 4.5 32-bit).cpr", 58)]
+    #[case::cubase_7_64_bit("Example Project (Cubase 7 64-bit).cpr", 58)]
+    #[case::cubase_85_64_bit("Example Project (Cubase 8.5 64-bit).cpr", 86)]
+    #[case::cubase_14("Example Project (Cubase 14).cpr", 86)]
+    fn test_get_project_details_instance_offsets(
+        #[case] filename: &str,
+        #[case] expected_panner_instances: usize,
+    ) {
+        let project_path = PathBuf::from("testdata").join(filename);
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let reader = Reader::new(&project_bytes);
+        let project_details = reader.get_project_details().unwrap();
+
+        let panner_instances = project_details
+            .instances
+            .iter()
+            .filter(|instance| instance.plugin.name == "Standard Panner")
+            .count();
+        assert_eq!(panner_instances, expected_panner_instances);
+
+        for instance in &project_details.instances {
+            assert_eq!(
+                &project_bytes[instance.offset..instance.offset + PLUGIN_UID_KEY.len()],
+                PLUGIN_UID_KEY
+            );
+        }
+        assert!(
+            project_details
+                .instances
+                .windows(2)
+                .all(|instances| instances[0].offset < instances[1].offset)
+        );
+    }
+
+    #[rstest]
+    #[case::cubase_45_32_bit("Example Project (Cubase 4.5 32-bit).cpr", 51)]
+    #[case::cubase_45_64_bit("Example Project (Cubase 4.5 64-bit).cpr", 51)]
+    #[case::cubase_7_64_bit("Example Project (Cubase 7 64-bit).cpr", 0)]
+    #[case::cubase_85_32_bit("Example Project (Cubase 8.5 32-bit).cpr", 28)]
+    #[case::cubase_85_64_bit("Example Project (Cubase 8.5 64-bit).cpr", 0)]
+    #[case::cubase_14("Example Project (Cubase 14).cpr", 0)]
+    fn test_get_project_details_instances_active(
+        #[case] filename: &str,
+        #[case] expected_deactivated_panner_instances: usize,
+    ) {
+        let project_path = PathBuf::from("testdata").join(filename);
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let reader = Reader::new(&project_bytes);
+        let project_details = reader.get_project_details().unwrap();
+
+        // Only the panners of unused sends are deactivated in the example projects.
+        let deactivated_instances = project_details
+            .instances
+            .iter()
+            .filter(|instance| !instance.active)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            deactivated_instances.len(),
+            expected_deactivated_panner_instances
+        );
+        assert!(
+            deactivated_instances
+                .iter()
+                .all(|instance| instance.plugin.name == "Standard Panner")
+        );
+        assert!(
+            project_details
+                .instances
+                .iter()
+                .all(|instance| !instance.bypassed)
+        );
+    }
+
+    #[rstest]
+    #[case::insert_folder("InsertFolder", "StudioEQ")]
+    #[case::strip_folder("StripFolder", "EQ")]
+    fn test_get_project_details_instances_bypassed(
+        #[case] folder_name: &str,
+        #[case] expected_plugin_name: &str,
+    ) {
+        let project_path = PathBuf::from("testdata").join("Example Project (Cubase 14).cpr");
+        let mut project_bytes = fs::read(project_path).unwrap();
+
+        // Bypass the first folder of the given kind, which belongs to the "Acid Bass" track.
+        let folder_index = project_bytes
+            .windows(folder_name.len())
+            .position(|window| window == folder_name.as_bytes())
+            .unwrap();
+        let bypass_index = folder_index
+            + project_bytes[folder_index..]
+                .windows(BYPASS_ATTRIBUTE.len())
+                .position(|window| window == BYPASS_ATTRIBUTE)
+                .unwrap()
+            + BYPASS_ATTRIBUTE.len();
+        project_bytes[bypass_index + 7] = 1;
+
+        let reader = Reader::new(&project_bytes);
+        let project_details = reader.get_project_details().unwrap();
+
+        let bypassed_instances = project_details
+            .instances
+            .iter()
+            .filter(|instance| instance.bypassed)
+            .map(|instance| {
+                (
+                    instance.plugin.name.as_str(),
+                    instance.channel.as_ref().unwrap().name.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bypassed_instances,
+            vec![(expected_plugin_name, "Acid Bass")]
+        );
+    }
+
+    #[rstest]
+    #[case::cubase_45_32_bit("Example Project (Cubase 4.5 32-bit).cpr")]
+    #[case::cubase_7_64_bit("Example Project (Cubase 7 64-bit).cpr")]
+    #[case::cubase_11("Example Project (Cubase 11).cpr")]
+    #[case::cubase_14("Example Project (Cubase 14).cpr")]
+    fn test_get_project_details_instance_categories(#[case] filename: &str) {
+        let project_path = PathBuf::from("testdata").join(filename);
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let reader = Reader::new(&project_bytes);
+        let project_details = reader.get_project_details().unwrap();
+
+        for instance in &project_details.instances {
+            let name = instance.plugin.name.as_str();
+            let expected_category = match name {
+                "Sylenth1" | "Omnisphere" | "Hive" => PluginCategory::Instrument,
+                "EQ" | "Input Filter" => PluginCategory::ChannelStrip,
+                "Standard Panner" => PluginCategory::Panner,
+                "ArtsAcousticReverb" => PluginCategory::SendEffect,
+                _ => PluginCategory::Insert,
+            };
+            assert_eq!((name, instance.category), (name, expected_category));
+        }
+    }
+
+    #[rstest]
+    #[case::midi_insert(0, 0, 1, None, PluginCategory::MidiInsert)]
+    #[case::midi_insert_in_insert_folder(
+        0,
+        0,
+        1,
+        Some(PluginCategory::Insert),
+        PluginCategory::MidiInsert
+    )]
+    #[case::instrument_without_context(0, 2, 1, None, PluginCategory::Instrument)]
+    #[case::effect_without_context(2, 2, 0, None, PluginCategory::Unknown)]
+    #[case::insert(2, 2, 0, Some(PluginCategory::Insert), PluginCategory::Insert)]
+    fn test_classify_plugin(
+        #[case] audio_inputs: i64,
+        #[case] audio_outputs: i64,
+        #[case] event_inputs: i64,
+        #[case] scope_category: Option<PluginCategory>,
+        #[case] expected_category: PluginCategory,
+    ) {
+        let attributes = Attributes {
+            offset: 0,
+            entries: [
+                ("Audio Input Count", audio_inputs),
+                ("Audio Output Count", audio_outputs),
+                ("Event Input Count", event_inputs),
+            ]
+            .into_iter()
+            .map(|(key, count)| attributes::Attribute {
+                key: key.into(),
+                offset: 0,
+                value: Value::Integer(count),
+            })
+            .collect(),
+            truncated: false,
+        };
+        let scope = Scope {
+            category: scope_category,
+            ..Scope::default()
+        };
+
+        assert_eq!(classify_plugin(&attributes, &scope), expected_category);
+    }
+
+    #[rstest]
+    #[case::audio_track(1, ChannelKind::AudioTrack)]
+    #[case::group_channel(2, ChannelKind::GroupChannel)]
+    fn test_collect_insert_on_channel(
+        #[case] channel_type: i64,
+        #[case] expected_channel_kind: ChannelKind,
+    ) {
+        let attribute = |key: &'static str, value| Attribute {
+            key: key.into(),
+            offset: 0,
+            value,
+        };
+        let attribute_list = |entries| Attributes {
+            offset: 0,
+            entries,
+            truncated: false,
+        };
+        let plugin = attribute_list(vec![
+            attribute(
+                "Plugin UID",
+                Value::Attributes(attribute_list(vec![attribute(
+                    "GUID",
+                    Value::String("77BBA7CA90F14C9BB298BA9010D6DD78".into()),
+                )])),
+            ),
+            attribute("Plugin Name", Value::String("StudioEQ".into())),
+            attribute("Audio Input Count", Value::Integer(2)),
+            attribute("Audio Output Count", Value::Integer(2)),
+        ]);
+        let channel = attribute_list(vec![
+            attribute(
+                "Name",
+                Value::Attributes(attribute_list(vec![attribute(
+                    "String",
+                    Value::String("Guitar".into()),
+                )])),
+            ),
+            attribute("Type", Value::Integer(channel_type)),
+            attribute(
+                "InsertFolder",
+                Value::Attributes(attribute_list(vec![attribute(
+                    "Slot",
+                    Value::List(vec![plugin]),
+                )])),
+            ),
+        ]);
+
+        let mut collector = PluginCollector::default();
+        collector
+            .collect_attributes(&channel, &Scope::default())
+            .unwrap();
+
+        assert_eq!(collector.instances.len(), 1);
+        let instance = &collector.instances[0];
+        assert_eq!(instance.plugin.name, "StudioEQ");
+        assert_eq!(
+            instance.channel,
+            Some(Channel {
+                name: "Guitar".to_string(),
+                kind: expected_channel_kind,
+            })
+        );
+        assert_eq!(instance.category, PluginCategory::Insert);
+    }
+
+    #[test]
+    fn test_merge_duplicate_instances() {
+        let instance = |name: &str, offset: usize| PluginInstance {
+            plugin: Plugin {
+                guid: format!("{name} GUID"),
+                name: name.to_string(),
+            },
+            channel: Some(Channel {
+                name: "Acid Bass".to_string(),
+                kind: ChannelKind::InstrumentTrack,
+            }),
+            category: PluginCategory::Insert,
+            offset,
+            active: true,
+            bypassed: false,
+            state: None,
+        };
+        let mut collector = PluginCollector {
+            instances: vec![instance("StudioEQ", 10), instance("TrackComp", 40)],
+            duplicate_instances: vec![
+                instance("StudioEQ", 20),
+                instance("StudioEQ", 30),
+                instance("TrackComp", 50),
+            ],
+            ..PluginCollector::default()
+        };
+
+        collector.merge_duplicate_instances();
+
+        // Only the second StudioEQ within the duplicate channel isn't already on the track.
+        assert_eq!(
+            collector.instances,
+            vec![
+                instance("StudioEQ", 10),
+                instance("StudioEQ", 30),
+                instance("TrackComp", 40),
+            ]
+        );
+        assert!(collector.duplicate_instances.is_empty());
+    }
+
     #[test]
     fn test_get_project_details_sx3() {
         let project_path = PathBuf::from("testdata").join("Example Project (Cubase SX3).cpr");
@@ -495,10 +1713,447 @@ fn test_get_project_details_sx3() {
                 application: "Cubase SX".to_string(),
                 version: "3.1.1".to_string(),
                 release_date: "Oct 13 2005".to_string(),
-                architecture: "Unspecified".to_string()
+                architecture: "Unspecified".to_string(),
+                tempo: Some(Tempo { bpm: 120.0 }),
+                tempo_track_active: Some(false),
+                time_signature: Some(TimeSignature {
+                    numerator: 4,
+                    denominator: 4,
+                }),
+                sample_rate: None,
+                bit_depth: None,
             }
         );
         assert!(project_details.plugins.is_empty());
+        assert!(project_details.instances.is_empty());
+    }
+
+    /// Obtains the offset of the value of an attribute, which follows its key and type.
+    fn attribute_value_offset(attribute: &Attribute) -> usize {
+        attribute.offset + attribute.key.len() + 1 + 2
+    }
+
+    #[test]
+    fn test_get_project_details_track_settings() {
+        let project_path =
+            PathBuf::from("testdata").join("Example Project (Cubase 4.5 32-bit).cpr");
+        let mut project_bytes = fs::read(project_path).unwrap();
+
+        // Change the fixed tempo and activate the tempo track, which follow the single tempo event,
+        // along with the numerator and denominator of the single time signature.
+        let archive = Archive::parse(&project_bytes).unwrap();
+        let tempo_index = archive
+            .objects()
+            .find(|object| object.class == "MTempoTrackEvent")
+            .unwrap()
+            .payload_offset
+            + 4
+            + TEMPO_EVENT_LENGTH;
+        let signature_index = archive
+            .objects()
+            .find(|object| object.class == "MSignatureTrackEvent")
+            .unwrap()
+            .payload_offset
+            + 8;
+        project_bytes[tempo_index..tempo_index + 4].copy_from_slice(&128.5_f32.to_be_bytes());
+        project_bytes[tempo_index + 5] = 1;
+        project_bytes[signature_index..signature_index + 2].copy_from_slice(&7_u16.to_be_bytes());
+        project_bytes[signature_index + 2..signature_index + 4]
+            .copy_from_slice(&8_u16.to_be_bytes());
+
+        let reader = Reader::new(&project_bytes);
+        let project_details = reader.get_project_details().unwrap();
+
+        assert_eq!(project_details.metadata.tempo, Some(Tempo { bpm: 128.5 }));
+        assert_eq!(project_details.metadata.tempo_track_active, Some(true));
+        assert_eq!(
+            project_details.metadata.time_signature,
+            Some(TimeSignature {
+                numerator: 7,
+                denominator: 8,
+            })
+        );
+    }
+
+    #[test]
+    fn test_get_project_details_media_settings() {
+        let project_path = PathBuf::from("testdata").join("Example Project (Cubase 14).cpr");
+        let mut project_bytes = fs::read(project_path).unwrap();
+
+        // Change the media attributes holding the tempo, sample rate and bit depth along with the
+        // numerator and denominator of the first time signature event.
+        let archive = Archive::parse(&project_bytes).unwrap();
+        let media_values = [
+            ("MusicalTempo", "Float", 93.5_f64.to_be_bytes()),
+            ("AudioSampleRate", "Float", 96_000_f64.to_be_bytes()),
+            ("AudioSampleSize", "Long", 24_i64.to_be_bytes()),
+        ];
+        let mut changes = media_values
+            .into_iter()
+            .map(|(id, key, value)| {
+                let attributes = read_media_attribute(&archive, id).unwrap();
+                let position = attributes.position(key).unwrap();
+                (attribute_value_offset(&attributes.entries[position]), value)
+            })
+            .collect::<Vec<_>>();
+        let signature_event = archive
+            .objects()
+            .find(|object| object.class == "MSignatureTrackEvent")
+            .unwrap()
+            .objects()
+            .flat_map(Object::attributes)
+            .find_map(|attributes| match attributes.get("SignatureEvent") {
+                Some(Value::Objects(events)) => events.first(),
+                _ => None,
+            })
+            .unwrap();
+        for (key, value) in [("Numerator", 7_i64), ("Denominator", 8)] {
+            let position = signature_event.attributes.position(key).unwrap();
+            let attribute = &signature_event.attributes.entries[position];
+            changes.push((attribute_value_offset(attribute), value.to_be_bytes()));
+        }
+        for (offset, value) in changes {
+            project_bytes[offset..offset + 8].copy_from_slice(&value);
+        }
+
+        let reader = Reader::new(&project_bytes);
+        let project_details = reader.get_project_details().unwrap();
+
+        assert_eq!(
+            project_details.metadata,
+            Metadata {
+                application: "Cubase".to_string(),
+                version: "14.0.30".to_string(),
+                release_date: "May 22 2025".to_string(),
+                architecture: "WIN64".to_string(),
+                tempo: Some(Tempo { bpm: 93.5 }),
+                tempo_track_active: Some(false),
+                time_signature: Some(TimeSignature {
+                    numerator: 7,
+                    denominator: 8,
+                }),
+                sample_rate: Some(96_000),
+                bit_depth: Some(24),
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::cubase_45_32_bit("Example Project (Cubase 4.5 32-bit).cpr", false)]
+    #[case::cubase_7_64_bit("Example Project (Cubase 7 64-bit).cpr", false)]
+    #[case::cubase_95("Example Project (Cubase 9.5).cpr", true)]
+    #[case::cubase_14("Example Project (Cubase 14).cpr", true)]
+    fn test_get_project_details_tracks(
+        #[case] filename: &str,
+        #[case] includes_instrument_automation_and_output_tracks: bool,
+    ) {
+        let project_path = PathBuf::from("testdata").join(filename);
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let reader = Reader::new(&project_bytes);
+        let project_details = reader.get_project_details().unwrap();
+
+        let track = |name: &str, kind, depth| Track {
+            name: name.to_string(),
+            kind,
+            depth,
+        };
+        let mut expected_tracks = vec![
+            track("", TrackKind::Tempo, 0),
+            track("", TrackKind::Signature, 0),
+            track("Acid Bass", TrackKind::Instrument, 0),
+        ];
+        if includes_instrument_automation_and_output_tracks {
+            expected_tracks.push(track("", TrackKind::Automation, 1));
+        }
+        expected_tracks.push(track("Solo Vocalist", TrackKind::Instrument, 0));
+        if includes_instrument_automation_and_output_tracks {
+            expected_tracks.push(track("", TrackKind::Automation, 1));
+        }
+        expected_tracks.extend([
+            track("Crazy Rebel", TrackKind::Midi, 0),
+            track("VST Instruments", TrackKind::Folder, 0),
+            track("Hive", TrackKind::Folder, 1),
+            track("Hive", TrackKind::RackInstrument, 2),
+            track("", TrackKind::Automation, 3),
+            track("Hive", TrackKind::RackInstrument, 2),
+            track("", TrackKind::Automation, 3),
+            track("FX Channels", TrackKind::Folder, 0),
+            track("FX 1-Reverb", TrackKind::Fx, 1),
+            track("", TrackKind::Automation, 2),
+        ]);
+        if includes_instrument_automation_and_output_tracks {
+            expected_tracks.extend([
+                track("Input/Output Channels", TrackKind::Folder, 0),
+                track("Stereo Out", TrackKind::Output, 1),
+            ]);
+        }
+        expected_tracks.push(track("Marker", TrackKind::Marker, 0));
+
+        assert_eq!(project_details.tracks, expected_tracks);
+    }
+
+    #[rstest]
+    #[case::cubase_sx3("Example Project (Cubase SX3).cpr")]
+    #[case::cubase_45_64_bit("Example Project (Cubase 4.5 64-bit).cpr")]
+    #[case::cubase_65_32_bit("Example Project (Cubase 6.5 32-bit).cpr")]
+    #[case::cubase_85_64_bit("Example Project (Cubase 8.5 64-bit).cpr")]
+    #[case::cubase_11("Example Project (Cubase 11).cpr")]
+    #[case::cubase_13("Example Project (Cubase 13).cpr")]
+    fn test_get_project_details_global_and_automation_tracks(#[case] filename: &str) {
+        let project_path = PathBuf::from("testdata").join(filename);
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let reader = Reader::new(&project_bytes);
+        let project_details = reader.get_project_details().unwrap();
+
+        for kind in [
+            TrackKind::Automation,
+            TrackKind::Marker,
+            TrackKind::Tempo,
+            TrackKind::Signature,
+        ] {
+            assert!(
+                project_details
+                    .tracks
+                    .iter()
+                    .any(|track| track.kind == kind)
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_project_details_tracks_sx3() {
+        let project_path = PathBuf::from("testdata").join("Example Project (Cubase SX3).cpr");
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let reader = Reader::new(&project_bytes);
+        let project_details = reader.get_project_details().unwrap();
+
+        let track_kinds = project_details
+            .tracks
+            .iter()
+            .map(|track| (track.name.as_str(), track.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            track_kinds[..7],
+            [
+                ("", TrackKind::Tempo),
+                ("", TrackKind::Signature),
+                ("Acid Bass", TrackKind::Midi),
+                ("Classic Strings", TrackKind::Midi),
+                ("Crazy Rebel", TrackKind::Midi),
+                ("VST Instruments", TrackKind::Folder),
+                ("Sylenth1", TrackKind::Folder),
+            ]
+        );
+        assert_eq!(track_kinds[7], ("Sylenth1", TrackKind::RackInstrument));
+        assert_eq!(track_kinds.last(), Some(&("Marker", TrackKind::Marker)));
+    }
+
+    #[rstest]
+    #[case::cubase_45_32_bit(
+        "Example Project (Cubase 4.5 32-bit).cpr",
+        "C:\\Users\\Fots\\Downloads\\1\\Example Project (Cubase 4.5 32-bit).cpr"
+    )]
+    #[case::cubase_14(
+        "Example Project (Cubase 14).cpr",
+        "C:\\Users\\Fots\\source\\cubase-project-plugins\\testdata\\Example Project (Cubase 14).cpr"
+    )]
+    fn test_read_path(#[case] filename: &str, #[case] expected_path: &str) {
+        let project_path = PathBuf::from("testdata").join(filename);
+        let project_bytes = fs::read(project_path).unwrap();
+
+        // The example projects have an empty pool, so the only path within it is the location of
+        // the project itself.
+        let archive = Archive::parse(&project_bytes).unwrap();
+        let pool = archive
+            .objects()
+            .find(|object| object.class == "PPool")
+            .unwrap();
+        let path = pool
+            .objects()
+            .find(|object| object.class == "FNPath")
+            .and_then(read_path);
+
+        assert_eq!(path.as_deref(), Some(expected_path));
+    }
+
+    #[rstest]
+    #[case::cubase_45_32_bit("Example Project (Cubase 4.5 32-bit).cpr")]
+    #[case::cubase_14("Example Project (Cubase 14).cpr")]
+    fn test_get_project_details_plugin_states(#[case] filename: &str) {
+        let project_path = PathBuf::from("testdata").join(filename);
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let reader = Reader::new(&project_bytes).with_plugin_states();
+        let project_details = reader.get_project_details().unwrap();
+
+        let state = |name: &str| {
+            project_details
+                .instances
+                .iter()
+                .find(|instance| instance.plugin.name == name)
+                .and_then(|instance| instance.state.as_ref())
+                .unwrap()
+        };
+
+        assert_eq!(
+            state("Omnisphere").preset_name.as_deref(),
+            Some("Classical Soprano Eh-Oh")
+        );
+        assert_eq!(state("Hive").preset_name.as_deref(), Some("Crazy Rebel"));
+        assert_eq!(state("Sylenth1").preset_name, None);
+        assert!(state("Sylenth1").component.starts_with(VST2_WRAPPER_ID));
+        assert!(state("Sylenth1").controller.is_empty());
+    }
+
+    #[test]
+    fn test_get_project_details_without_plugin_states() {
+        let project_path = PathBuf::from("testdata").join("Example Project (Cubase 14).cpr");
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let reader = Reader::new(&project_bytes);
+        let project_details = reader.get_project_details().unwrap();
+
+        assert!(
+            project_details
+                .instances
+                .iter()
+                .all(|instance| instance.state.is_none())
+        );
+    }
+
+    #[rstest]
+    #[case::vst2_program(b"VstW\0\0\0\x08\0\0\0\x01\0\0\0\0CcnK\0\0\0\x40FxCk\0\0\0\x01abcd\0\0\0\x01\0\0\0\x02Warm Pad\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0", Some("Warm Pad"))]
+    #[case::vst2_bank(b"VstW\0\0\0\x08\0\0\0\x01\0\0\0\0CcnK\0\0\0\x40FBCh\0\0\0\x02abcd\0\0\0\x01\0\0\0\x02Warm Pad\0", None)]
+    #[case::patch(b"\x10\0\0\0#pgm=Crazy Rebel.h2p\n/*@Meta", Some("Crazy Rebel"))]
+    #[case::patch_with_dot(b"#pgm=Mr. Bass 2\n", Some("Mr. Bass 2"))]
+    #[case::entry_description(
+        b"<ENTRYDESCR  name=\"Default\" >\0<ENTRYDESCR  name=\"Keys &amp; Pads\"  library=\"Library\" >",
+        Some("Keys & Pads")
+    )]
+    #[case::default_entry_description(b"<ENTRYDESCR  name=\"Default\" >", Some("Default"))]
+    #[case::unrecognised(b"\x30\x06\0\0input\0\0\0", None)]
+    fn test_read_preset_name(#[case] state: &[u8], #[case] expected_name: Option<&str>) {
+        assert_eq!(read_preset_name(state).as_deref(), expected_name);
+    }
+
+    #[test]
+    fn test_get_project_details_media() {
+        let project_path = PathBuf::from("testdata").join("Example Project (Cubase 14).cpr");
+        let project_bytes = fs::read(project_path).unwrap();
+
+        let reader = Reader::new(&project_bytes);
+        let project_details = reader.get_project_details().unwrap();
+
+        assert!(project_details.media.is_empty());
+    }
+
+    /// Builds a string preceded by its length and followed by a nul terminator.
+    fn build_string(string: &str) -> Vec<u8> {
+        let mut bytes = u32::try_from(string.len() + 1)
+            .unwrap()
+            .to_be_bytes()
+            .to_vec();
+        bytes.extend(string.as_bytes());
+        bytes.push(0);
+        bytes
+    }
+
+    /// Builds an object of the given class which declares its class.
+    fn build_object(class: &str, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = u32::MAX.to_be_bytes().to_vec();
+        bytes.extend(build_string(class));
+        bytes.extend(0_u16.to_be_bytes());
+        bytes.extend(u32::try_from(payload.len()).unwrap().to_be_bytes());
+        bytes.extend(payload);
+        bytes
+    }
+
+    /// Builds the fields of a `FNPath` object with the given file name and directory.
+    fn build_file_path(name: &str, directory: &str) -> Vec<u8> {
+        let mut bytes = build_string(name);
+        bytes.extend(b"WAVE");
+        for field in ["wav", "wav", "Wave File"] {
+            bytes.extend(build_string(field));
+        }
+        bytes.extend([0; PATH_DIRECTORY_OFFSET]);
+        bytes.extend(build_string(directory));
+        bytes
+    }
+
+    #[test]
+    fn test_read_media_files() {
+        let mut node_path = 2_u32.to_be_bytes().to_vec();
+        node_path.extend(build_string("NodePath"));
+        node_path.extend(8_u16.to_be_bytes());
+        node_path.extend(build_string("Audio\\Bass.wav"));
+        node_path.extend(build_string("IsRelativePath"));
+        node_path.extend(1_u16.to_be_bytes());
+        node_path.extend(1_i64.to_be_bytes());
+
+        let mut directory_path = vec![0; PATH_DIRECTORY_OFFSET];
+        directory_path.extend(build_string("Audio\\Drums.wav"));
+
+        let entry = [
+            node_path,
+            build_object("FNPath", &build_file_path("Vocals.wav", "C:\\Audio\\")),
+            build_object("FPath", &directory_path),
+            build_object("FNPath", &build_file_path("", "C:\\Audio\\")),
+            build_object("FNPath", &build_file_path("Vocals.wav", "C:\\Audio\\")),
+        ]
+        .concat();
+        let pool = [
+            build_object("FNPath", &build_file_path("Song.cpr", "C:\\Projects\\")),
+            build_object("PAudioClip", &entry),
+        ]
+        .concat();
+        let arch_data = build_object("PPool", &pool);
+
+        let mut project_bytes = b"RIFF\0\0\0\0NUND".to_vec();
+        for (chunk_id, data) in [
+            (
+                &b"ROOT"[..],
+                [build_string("Pool"), build_string("PPool")].concat(),
+            ),
+            (b"ARCH", arch_data),
+        ] {
+            project_bytes.extend(chunk_id);
+            project_bytes.extend(u32::try_from(data.len()).unwrap().to_be_bytes());
+            project_bytes.extend(data);
+        }
+        let archive = Archive::parse(&project_bytes).unwrap();
+
+        let media_files = read_media_files(&archive);
+
+        assert_eq!(
+            media_files,
+            vec![
+                MediaFile {
+                    path: "Audio\\Bass.wav".to_string(),
+                    relative: true,
+                },
+                MediaFile {
+                    path: "C:\\Audio\\Vocals.wav".to_string(),
+                    relative: false,
+                },
+                MediaFile {
+                    path: "Audio\\Drums.wav".to_string(),
+                    relative: true,
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::windows_drive("C:\\Audio\\Kick.wav", true)]
+    #[case::windows_network("\\\\Server\\Audio\\Kick.wav", true)]
+    #[case::macos("/Users/fots/Audio/Kick.wav", true)]
+    #[case::relative_directory("Audio\\Kick.wav", false)]
+    #[case::relative_file("Kick.wav", false)]
+    fn test_is_absolute_path(#[case] path: &str, #[case] expected_absolute: bool) {
+        assert_eq!(is_absolute_path(path), expected_absolute);
     }
 
     #[rstest]
@@ -0,0 +1,623 @@
+use std::{borrow::Cow, collections::HashMap, path::Path};
+
+use anyhow::Error;
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use cubase_project_plugins::{
+    Channel, Metadata, Plugin, PluginCategory, PluginFormat, PluginInstance, ProjectDiff,
+    ProjectFileKind, Track,
+};
+use serde::Serialize;
+
+use crate::{
+    catalog::{Catalog, CatalogEntry},
+    cli::StateEncoding,
+    inventory::PluginStatus,
+};
+
+/// The name used for the vendor of plugins which aren't listed in the catalog.
+pub const UNKNOWN_VENDOR: &str = "Unknown vendor";
+
+/// A Cubase project which was successfully scanned.
+#[derive(Debug, Serialize)]
+pub struct ProjectReport {
+    /// Path of the Cubase project file.
+    pub path: String,
+    /// Kind of file the project was read from, such as a backup.
+    pub file_kind: ProjectFileKind,
+    /// Metadata describing the Cubase version used to create the project.
+    pub metadata: Metadata,
+    /// Plugins used in the project, sorted by name.
+    pub plugins: Vec<PluginReport>,
+    /// Media files referenced by the pool of the project, when media files are listed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media: Option<Vec<MediaReport>>,
+    /// Tracks in the project window, when tracks are listed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracks: Option<Vec<Track>>,
+}
+
+/// A media file referenced by a project along with whether it exists.
+#[derive(Debug, Serialize)]
+pub struct MediaReport {
+    /// Path of the file as stored in the project.
+    pub path: String,
+    /// Path of the file resolved against the directory containing the project file.
+    pub resolved_path: String,
+    /// Whether the path is relative to the directory containing the project file.
+    pub relative: bool,
+    /// Whether the file exists.
+    pub exists: bool,
+}
+
+/// A plugin used in a project along with the tracks and channels which use it.
+#[derive(Debug, Serialize)]
+pub struct PluginReport {
+    /// The plugin which is used.
+    #[serde(flatten)]
+    pub plugin: Plugin,
+    /// Format of the plugin as determined from its GUID.
+    pub format: PluginFormat,
+    /// Vendor and product details of the plugin, when it's listed in the catalog.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub catalog: Option<CatalogEntry>,
+    /// Number of instances of the plugin in the project.
+    pub instances: i32,
+    /// Number of instances of the plugin which are bypassed or deactivated.
+    pub inactive_instances: i32,
+    /// Whether the plugin is installed, when an inventory of installed plugins is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<PluginStatus>,
+    /// Categories which the plugin is used in, in the order they first appear in the project.
+    pub categories: Vec<PluginCategory>,
+    /// Tracks and channels which use the plugin, in the order they appear in the project.
+    pub channels: Vec<Channel>,
+    /// Saved state of each instance of the plugin, when plugin states are read.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub states: Option<Vec<StateReport>>,
+}
+
+/// The saved state of a single plugin instance.
+#[derive(Debug, Serialize)]
+pub struct StateReport {
+    /// The track or channel which the plugin instance belongs to, if it could be determined.
+    pub channel: Option<Channel>,
+    /// Name of the preset or patch loaded into the plugin, if it could be determined.
+    pub preset_name: Option<String>,
+    /// State of the audio processor of the plugin.
+    pub component: String,
+    /// State of the edit controller of the plugin.
+    pub controller: String,
+    /// Path of the preset file which the state was written to, when presets are exported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset_path: Option<String>,
+}
+
+impl StateReport {
+    /// Describes the state of a plugin instance, encoding the state in the given format.
+    pub fn new(
+        instance: &PluginInstance,
+        encoding: StateEncoding,
+        preset_path: Option<&Path>,
+    ) -> Option<Self> {
+        let state = instance.state.as_ref()?;
+        let encode = |bytes: &[u8]| match encoding {
+            StateEncoding::Hex => hex::encode_upper(bytes),
+            StateEncoding::Base64 => STANDARD.encode(bytes),
+        };
+
+        Some(Self {
+            channel: instance.channel.clone(),
+            preset_name: state.preset_name.clone(),
+            component: encode(&state.component),
+            controller: encode(&state.controller),
+            preset_path: preset_path.map(|preset_path| preset_path.display().to_string()),
+        })
+    }
+}
+
+/// A project which uses a plugin being looked up, as listed by `--who-uses`.
+#[derive(Debug, Serialize)]
+pub struct ProjectUsageRecord {
+    /// Path of the Cubase project file.
+    pub path: String,
+    /// Version of Cubase used to create the project.
+    pub cubase_version: String,
+    /// Architecture of the Cubase version used to create the project.
+    pub architecture: String,
+    /// Time the project file was last modified in RFC 3339 format, if it could be determined.
+    pub modified: Option<String>,
+    /// Number of instances of matching plugins in the project.
+    pub instances: i32,
+}
+
+/// The complete machine-readable result of looking up which projects use a plugin.
+#[derive(Debug, Serialize)]
+pub struct ProjectUsageReport<'a> {
+    /// All projects which use a matching plugin, in the requested order.
+    pub projects: &'a [ProjectUsageRecord],
+    /// All errors which were encountered, in the order they occurred.
+    pub errors: &'a [ErrorReport],
+}
+
+/// The complete machine-readable result of comparing two projects.
+#[derive(Debug, Serialize)]
+pub struct DiffReport<'a> {
+    /// Path of the old Cubase project file.
+    pub old_path: String,
+    /// Path of the new Cubase project file.
+    pub new_path: String,
+    /// Differences between the old and new projects.
+    #[serde(flatten)]
+    pub diff: &'a ProjectDiff,
+}
+
+/// A saved version of a project, as listed by the history command.
+#[derive(Debug, Serialize)]
+pub struct ProjectVersionReport {
+    /// Path of the Cubase project file.
+    pub path: String,
+    /// Version number from the suffix of the file name, or 0 when it has no suffix.
+    pub number: u32,
+    /// Whether the file is a backup which Cubase wrote when the project was saved.
+    pub backup: bool,
+    /// Version of Cubase used to save the project.
+    pub cubase_version: String,
+    /// Architecture of the Cubase version used to save the project.
+    pub architecture: String,
+    /// Time the project file was last modified in RFC 3339 format, if it could be determined.
+    pub modified: Option<String>,
+    /// All plugins used in the project, sorted by name.
+    pub plugins: Vec<Plugin>,
+    /// Differences from the previous version, which are absent for the first version.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<ProjectDiff>,
+}
+
+/// All saved versions of a project, as listed by the history command.
+#[derive(Debug, Serialize)]
+pub struct ProjectHistoryReport {
+    /// Path of the folder containing the project.
+    pub folder: String,
+    /// Name of the project without its version suffix or extension.
+    pub base_name: String,
+    /// Saved versions of the project in the requested order.
+    pub versions: Vec<ProjectVersionReport>,
+}
+
+/// The complete machine-readable result of showing how projects evolved across their versions.
+#[derive(Debug, Serialize)]
+pub struct HistoryReport<'a> {
+    /// All projects along with their saved versions, sorted by folder and name.
+    pub projects: &'a [ProjectHistoryReport],
+    /// All errors which were encountered, in the order they occurred.
+    pub errors: &'a [ErrorReport],
+}
+
+/// An error which was encountered while scanning a path.
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    /// Path of the directory or project file which caused the error.
+    pub path: String,
+    /// Top-level description of the error.
+    pub error: String,
+    /// Underlying causes of the error, outermost first.
+    pub causes: Vec<String>,
+}
+
+impl ErrorReport {
+    pub fn new(path: &Path, error: &Error) -> Self {
+        let mut chain = error.chain().map(ToString::to_string);
+        Self {
+            path: path.display().to_string(),
+            error: chain.next().unwrap_or_default(),
+            causes: chain.collect(),
+        }
+    }
+}
+
+/// Tracks how much a particular plugin is used across projects.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PluginUsage {
+    /// Number of projects using the plugin.
+    pub projects: i32,
+    /// Total number of instances of the plugin across all projects.
+    pub instances: i32,
+}
+
+/// The number of projects and instances which use a particular plugin.
+#[derive(Debug, Serialize)]
+pub struct PluginCount<'a> {
+    /// Globally unique identifier for the plugin.
+    pub guid: &'a str,
+    /// Name of the plugin.
+    pub name: &'a str,
+    /// Vendor of the plugin, when it's listed in the catalog.
+    pub vendor: Option<&'a str>,
+    /// Whether the plugin is bundled with Cubase rather than being a third-party plugin.
+    pub stock: bool,
+    /// Number of projects using the plugin.
+    pub count: i32,
+    /// Total number of instances of the plugin across all projects.
+    pub instances: i32,
+}
+
+impl<'a> PluginCount<'a> {
+    /// Converts plugin usage into a list sorted by plugin name, looking up the vendor of each
+    /// plugin in the catalog.
+    pub fn sorted(
+        plugin_usage: impl IntoIterator<Item = (&'a Plugin, &'a PluginUsage)>,
+        catalog: &'a Catalog,
+    ) -> Vec<Self> {
+        let mut sorted_plugin_counts = plugin_usage
+            .into_iter()
+            .map(|(plugin, usage)| Self {
+                guid: &plugin.guid,
+                name: &plugin.name,
+                vendor: catalog.vendor(&plugin.guid),
+                stock: catalog.is_stock(&plugin.guid),
+                count: usage.projects,
+                instances: usage.instances,
+            })
+            .collect::<Vec<_>>();
+        sorted_plugin_counts.sort_by_cached_key(|plugin_count| {
+            (plugin_count.name.to_lowercase(), plugin_count.guid)
+        });
+        sorted_plugin_counts
+    }
+}
+
+/// The number of projects and instances which use a particular plugin which isn't installed.
+#[derive(Debug, Serialize)]
+pub struct MissingPluginCount<'a> {
+    /// The number of projects and instances which use the plugin.
+    #[serde(flatten)]
+    pub plugin_count: PluginCount<'a>,
+    /// Whether the plugin is missing or only installed with a different GUID.
+    pub status: PluginStatus,
+}
+
+/// The number of projects and instances which use a particular VST2 plugin which isn't bundled
+/// with Cubase.
+#[derive(Debug, Serialize)]
+pub struct Vst2PluginCount<'a> {
+    /// The number of projects and instances which use the plugin.
+    #[serde(flatten)]
+    pub plugin_count: PluginCount<'a>,
+    /// The four-character unique ID of the plugin.
+    pub unique_id: String,
+    /// The start of the name of the plugin in lowercase.
+    pub name_prefix: String,
+}
+
+impl<'a> Vst2PluginCount<'a> {
+    /// Selects the VST2 plugins from a list of plugin counts.
+    pub fn from_plugin_counts(plugin_counts: Vec<PluginCount<'a>>) -> Vec<Self> {
+        plugin_counts
+            .into_iter()
+            .filter_map(|plugin_count| {
+                match PluginFormat::from_guid(plugin_count.guid, plugin_count.stock) {
+                    PluginFormat::Vst2 {
+                        unique_id,
+                        name_prefix,
+                    } => Some(Self {
+                        plugin_count,
+                        unique_id,
+                        name_prefix,
+                    }),
+                    PluginFormat::Vst3 | PluginFormat::BuiltIn => None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The number of projects and instances which use plugins made by a particular vendor.
+#[derive(Debug, Serialize)]
+pub struct VendorCount<'a> {
+    /// Vendor of the plugins, or `None` for plugins which aren't listed in the catalog.
+    pub vendor: Option<&'a str>,
+    /// Number of projects using plugins made by this vendor.
+    pub count: i32,
+    /// Total number of instances of plugins made by this vendor across all projects.
+    pub instances: i32,
+    /// Plugins made by this vendor, sorted by plugin name.
+    pub plugins: Vec<PluginCount<'a>>,
+}
+
+impl<'a> VendorCount<'a> {
+    /// Groups plugin counts by vendor into a list sorted by vendor name, with plugins which
+    /// aren't listed in the catalog last.
+    pub fn sorted(
+        vendor_usage: &'a HashMap<Option<String>, PluginUsage>,
+        plugin_counts: Vec<PluginCount<'a>>,
+    ) -> Vec<Self> {
+        let mut sorted_vendor_counts = vendor_usage
+            .iter()
+            .map(|(vendor, usage)| Self {
+                vendor: vendor.as_deref(),
+                count: usage.projects,
+                instances: usage.instances,
+                plugins: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        sorted_vendor_counts.sort_by_cached_key(|vendor_count| {
+            (
+                vendor_count.vendor.is_none(),
+                vendor_count.vendor.map(str::to_lowercase),
+            )
+        });
+
+        for plugin_count in plugin_counts {
+            if let Some(vendor_count) = sorted_vendor_counts
+                .iter_mut()
+                .find(|vendor_count| vendor_count.vendor == plugin_count.vendor)
+            {
+                vendor_count.plugins.push(plugin_count);
+            }
+        }
+        sorted_vendor_counts.retain(|vendor_count| !vendor_count.plugins.is_empty());
+        sorted_vendor_counts
+    }
+}
+
+/// The number of projects and instances which use plugins of a particular category.
+#[derive(Debug, Serialize)]
+pub struct CategoryCount {
+    /// Category of the plugins.
+    pub category: PluginCategory,
+    /// Number of projects using plugins of this category.
+    pub count: i32,
+    /// Total number of instances of plugins of this category across all projects.
+    pub instances: i32,
+}
+
+impl CategoryCount {
+    /// Converts category usage into a list sorted by category.
+    pub fn sorted(category_usage: &HashMap<PluginCategory, PluginUsage>) -> Vec<Self> {
+        let mut sorted_category_counts = category_usage
+            .iter()
+            .map(|(&category, usage)| Self {
+                category,
+                count: usage.projects,
+                instances: usage.instances,
+            })
+            .collect::<Vec<_>>();
+        sorted_category_counts.sort_by_key(|category_count| category_count.category);
+        sorted_category_counts
+    }
+}
+
+/// The number of projects which were created with a particular Cubase version.
+#[derive(Debug, Serialize)]
+pub struct CubaseVersionCount<'a> {
+    /// Cubase version including the application name and architecture.
+    pub version: &'a str,
+    /// Number of projects created with this Cubase version.
+    pub count: i32,
+}
+
+impl<'a> CubaseVersionCount<'a> {
+    /// Converts Cubase version counts into a list sorted naturally by version.
+    pub fn sorted(cubase_version_counts: &'a HashMap<String, i32>) -> Vec<Self> {
+        let mut sorted_cubase_version_counts = cubase_version_counts
+            .iter()
+            .map(|(version, &count)| Self { version, count })
+            .collect::<Vec<_>>();
+        sorted_cubase_version_counts
+            .sort_by(|a, b| natord::compare_ignore_case(a.version, b.version));
+        sorted_cubase_version_counts
+    }
+}
+
+/// The number of projects which use a particular sample rate.
+#[derive(Debug, Serialize)]
+pub struct SampleRateCount<'a> {
+    /// Sample rate of the projects, or "Unspecified" for projects which don't store it.
+    pub sample_rate: &'a str,
+    /// Number of projects using this sample rate.
+    pub count: i32,
+}
+
+impl<'a> SampleRateCount<'a> {
+    /// Converts sample rate counts into a list sorted naturally by sample rate.
+    pub fn sorted(sample_rate_counts: &'a HashMap<String, i32>) -> Vec<Self> {
+        let mut sorted_sample_rate_counts = sample_rate_counts
+            .iter()
+            .map(|(sample_rate, &count)| Self { sample_rate, count })
+            .collect::<Vec<_>>();
+        sorted_sample_rate_counts
+            .sort_by(|a, b| natord::compare_ignore_case(a.sample_rate, b.sample_rate));
+        sorted_sample_rate_counts
+    }
+}
+
+/// Plugin and Cubase version usage across all scanned projects.
+#[derive(Debug, Serialize)]
+pub struct Summaries<'a> {
+    /// Plugins used in 32-bit projects.
+    pub plugins_32_bit: Vec<PluginCount<'a>>,
+    /// Plugins used in 64-bit projects.
+    pub plugins_64_bit: Vec<PluginCount<'a>>,
+    /// Plugins used in all projects.
+    pub plugins: Vec<PluginCount<'a>>,
+    /// Plugins used in all projects which aren't installed, when an inventory of installed
+    /// plugins is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing_plugins: Option<Vec<MissingPluginCount<'a>>>,
+    /// VST2 plugins used in all projects which aren't bundled with Cubase.
+    pub vst2_plugins: Vec<Vst2PluginCount<'a>>,
+    /// Plugins used in all projects grouped by vendor.
+    pub vendors: Vec<VendorCount<'a>>,
+    /// Categories of plugins used in all projects.
+    pub categories: Vec<CategoryCount>,
+    /// Cubase versions used to create the projects.
+    pub cubase_versions: Vec<CubaseVersionCount<'a>>,
+    /// Sample rates used by the projects.
+    pub sample_rates: Vec<SampleRateCount<'a>>,
+}
+
+/// The complete machine-readable result of a scan.
+#[derive(Debug, Serialize)]
+pub struct Report<'a> {
+    /// All projects which were reported, in the order they were scanned.
+    pub projects: &'a [ProjectReport],
+    /// All errors which were encountered, in the order they occurred.
+    pub errors: &'a [ErrorReport],
+    /// Summaries of plugin and Cubase version usage.
+    pub summaries: Summaries<'a>,
+}
+
+/// A single plugin used in a project, as written to a CSV file.
+#[derive(Debug, Serialize)]
+pub struct PluginUsageRecord<'a> {
+    /// Path of the Cubase project file.
+    pub path: &'a str,
+    /// Kind of file the project was read from, such as a backup.
+    pub file_kind: ProjectFileKind,
+    /// Globally unique identifier for the plugin.
+    pub guid: &'a str,
+    /// Name of the plugin.
+    pub name: &'a str,
+    /// Format of the plugin.
+    pub format: String,
+    /// Vendor of the plugin, empty when it isn't listed in the catalog.
+    pub vendor: Option<&'a str>,
+    /// Whether the plugin is bundled with Cubase rather than being a third-party plugin.
+    pub stock: bool,
+    /// Number of instances of the plugin in the project.
+    pub instances: i32,
+    /// Number of instances of the plugin which are bypassed or deactivated.
+    pub inactive_instances: i32,
+    /// Whether the plugin is installed, empty when no inventory of installed plugins is used.
+    pub status: Option<PluginStatus>,
+    /// Categories which the plugin is used in, separated by commas.
+    pub categories: String,
+    /// Version of Cubase used to create the project.
+    pub cubase_version: &'a str,
+    /// Architecture of the Cubase version used to create the project.
+    pub architecture: &'a str,
+}
+
+impl<'a> PluginUsageRecord<'a> {
+    /// Flattens project reports into one record per plugin in each project.
+    pub fn from_projects(projects: &'a [ProjectReport]) -> impl Iterator<Item = Self> {
+        projects.iter().flat_map(|project| {
+            project.plugins.iter().map(|plugin| Self {
+                path: &project.path,
+                file_kind: project.file_kind,
+                guid: &plugin.plugin.guid,
+                name: &plugin.plugin.name,
+                format: plugin.format.to_string(),
+                vendor: plugin.catalog.as_ref().map(|entry| entry.vendor.as_str()),
+                stock: plugin.catalog.as_ref().is_some_and(|entry| entry.stock),
+                instances: plugin.instances,
+                inactive_instances: plugin.inactive_instances,
+                status: plugin.status,
+                categories: plugin
+                    .categories
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                cubase_version: &project.metadata.version,
+                architecture: &project.metadata.architecture,
+            })
+        })
+    }
+}
+
+/// A single summary count, as written to a CSV file.
+#[derive(Debug, Serialize)]
+pub struct SummaryRecord<'a> {
+    /// Name of the summary the count belongs to.
+    pub summary: &'a str,
+    /// Globally unique identifier for the plugin, empty for other counts.
+    pub guid: &'a str,
+    /// Name of the plugin, the vendor, the plugin category, the Cubase version or the sample rate.
+    pub name: Cow<'a, str>,
+    /// Number of projects.
+    pub count: i32,
+    /// Total number of plugin instances, empty for Cubase version and sample rate counts.
+    pub instances: Option<i32>,
+    /// Whether the plugin is installed, only present for missing plugin counts.
+    pub status: Option<PluginStatus>,
+}
+
+impl<'a> SummaryRecord<'a> {
+    /// Flattens all summaries into a list of records, keyed by the summary name.
+    pub fn from_summaries(summaries: &'a Summaries<'a>) -> Vec<Self> {
+        let plugin_summaries = [
+            ("plugins_32_bit", &summaries.plugins_32_bit),
+            ("plugins_64_bit", &summaries.plugins_64_bit),
+            ("plugins", &summaries.plugins),
+        ];
+
+        plugin_summaries
+            .into_iter()
+            .flat_map(|(summary, plugin_counts)| {
+                plugin_counts.iter().map(move |plugin_count| Self {
+                    summary,
+                    guid: plugin_count.guid,
+                    name: plugin_count.name.into(),
+                    count: plugin_count.count,
+                    instances: Some(plugin_count.instances),
+                    status: None,
+                })
+            })
+            .chain(
+                summaries
+                    .missing_plugins
+                    .iter()
+                    .flatten()
+                    .map(|missing_plugin_count| Self {
+                        summary: "missing_plugins",
+                        guid: missing_plugin_count.plugin_count.guid,
+                        name: missing_plugin_count.plugin_count.name.into(),
+                        count: missing_plugin_count.plugin_count.count,
+                        instances: Some(missing_plugin_count.plugin_count.instances),
+                        status: Some(missing_plugin_count.status),
+                    }),
+            )
+            .chain(summaries.vst2_plugins.iter().map(|vst2_plugin_count| Self {
+                summary: "vst2_plugins",
+                guid: vst2_plugin_count.plugin_count.guid,
+                name: vst2_plugin_count.plugin_count.name.into(),
+                count: vst2_plugin_count.plugin_count.count,
+                instances: Some(vst2_plugin_count.plugin_count.instances),
+                status: None,
+            }))
+            .chain(summaries.vendors.iter().map(|vendor_count| Self {
+                summary: "vendors",
+                guid: "",
+                name: vendor_count.vendor.unwrap_or(UNKNOWN_VENDOR).into(),
+                count: vendor_count.count,
+                instances: Some(vendor_count.instances),
+                status: None,
+            }))
+            .chain(summaries.categories.iter().map(|category_count| Self {
+                summary: "categories",
+                guid: "",
+                name: category_count.category.to_string().into(),
+                count: category_count.count,
+                instances: Some(category_count.instances),
+                status: None,
+            }))
+            .chain(summaries.cubase_versions.iter().map(|version_count| Self {
+                summary: "cubase_versions",
+                guid: "",
+                name: version_count.version.into(),
+                count: version_count.count,
+                instances: None,
+                status: None,
+            }))
+            .chain(summaries.sample_rates.iter().map(|sample_rate_count| Self {
+                summary: "sample_rates",
+                guid: "",
+                name: sample_rate_count.sample_rate.into(),
+                count: sample_rate_count.count,
+                instances: None,
+                status: None,
+            }))
+            .collect()
+    }
+}
@@ -0,0 +1,117 @@
+//! Plugins which are bundled with Cubase by Steinberg.
+
+/// GUIDs and names of the plugins which are bundled with Cubase, including the VST2 plugins which
+/// shipped with earlier versions.
+pub const STOCK_PLUGINS: &[(&str, &str)] = &[
+    // Special Track Types
+    ("D1B42E80F1124DFEAFEDE2480EFB4298", "Sampler Track"),
+    // Channel Strip
+    ("D56B9C6CA4F946018EED73EB83A74B58", "Input Filter"),
+    ("44E1149EDB3E4387BDD827FEA3A39EE7", "Standard Panner"),
+    ("C3B0615A2A444991B423673DEE2379A7", "Noise Gate"),
+    ("E022B5972163463CBA2036708D5AF5A5", "Standard Compressor"),
+    ("7186B3CC877647BAADD5EAC0785001DF", "Tube Compressor"),
+    ("2CA7A4D872A14FDD99B4932F2FD98854", "VintageCompressor"),
+    ("297BA567D83144E1AE921DEF07B41156", "EQ"),
+    ("464DF4539C164C03869900DF86BD887F", "DeEsser"),
+    ("051F2973F3B0488895948E8F6D51461D", "EnvelopeShaper"),
+    ("0F8B309075D044C0846CF8C4F703DB14", "Magneto II"),
+    ("12597CCB1D564942AEE0817D1223C384", "Tape Saturation"),
+    ("59131618D1BA4F12BE0DC717C765F214", "Tube Saturation"),
+    ("C7357C68564844EE8DF77B6E303819E1", "Brickwall Limiter"),
+    ("D5376CF1351C4E45B3170016D89E4D70", "Maximizer"),
+    ("76005C045DD848B993D3F39249C470C9", "Standard Limiter"),
+    // Instruments
+    ("91585860BA1748E581441ECD96B153ED", "Groove Agent SE"),
+    ("5B6D6402C5F74C35B3BE88ADF7FC7D27", "HALion Sonic SE"),
+    ("C56228EAE1B72952EF92E0F7EE157CB7", "LoopMash"),
+    ("745C69937EB44378A9CC237A3D758B16", "Mystic"),
+    ("F38B6C9C04CC45C8B98A682A6F45424A", "Padshop"),
+    ("FFF583CCDFB246F894308DB9C5D94C8D", "Prologue"),
+    ("CC3695D88FE74881B46E6CCFFB291CFF", "Retrologue"),
+    ("6790343791E94AE79D617D85146881AC", "Spector"),
+    // Effects
+    ("E4B91D8420B74C48A8B10F2DB9CB707E", "AmpSimulator"),
+    ("1CA6E894E4624F73ADEB29CD01DDE9EE", "AutoPan"),
+    ("56535441483930626974637275736865", "Bitcrusher"),
+    ("94DEB7BF378041EE9E2FEDA24E19EF60", "Brickwall Limiter"),
+    ("56535443686F3363686F707065720000", "Chopper"),
+    ("341FC589831D46A7A506BC0799E882AE", "Chorus"),
+    ("FE9EFEF6C7624335AA9799140ACE88C4", "Cloner"),
+    ("5B38F28281144FFE80285FF7CCF20483", "Compressor"),
+    ("56535444615475646174756265000000", "DaTube"),
+    ("75FD13A528D24880982197D541BC582A", "DeEsser"),
+    ("A990C1062CDE43839ECEF8FE91743DA5", "Distortion"),
+    ("C786544E675348D683EF9436D63EBD29", "Distroyer"),
+    ("B023870608424FABBCF5516BB15FF0EE", "DJ-Eq"),
+    ("6143DAECD6184AE2A570FE9F35065E24", "DualFilter"),
+    ("C3D60417A5BB4FB288CB1A75FA641EDF", "EnvelopeShaper"),
+    ("2A4C06FF24F14078868891D184CEFB73", "Expander"),
+    ("FDD7243578EF434A833705ECC4E4CE46", "Flanger"),
+    ("01F6CCC94CAE4668B7C6EC85E681E419", "Frequency"),
+    ("3B660266B3CA4B57BBD487AE1E6C0D2A", "Gate"),
+    ("7C215D9E31E2419E9925056D19310ACD", "GEQ-10"),
+    ("A491EAC9793A4A8790C4AC862DA1272E", "GEQ-30"),
+    ("565354477275676772756E67656C697A", "Grungelizer"),
+    ("71EDAB139B8740F78CC418BB21980B08", "Imager"),
+    ("B94789B3C4C944EFB0058694DAB8704E", "Limiter"),
+    ("D503488792F2EDE2D26FF9CEA6F7635F", "LoopMash FX"),
+    ("B8874B5BFF884A93A524C74D7FFB1D54", "Magneto II"),
+    ("44A0C349905B45D0B97C72D2C6F5B565", "Maximizer"),
+    ("5653544D6574336D6574616C697A6572", "Metalizer"),
+    ("565354614774656D6964696761746500", "MidiGate"),
+    ("5653544D6936326D697836746F320000", "Mix6To2"),
+    ("4A18B5A88A6B44D4B78F6E2FCC4746A2", "MixConvert V6"),
+    ("56535453444D436D6978657264656C61", "MixerDelay"),
+    ("27994C1910A04BA991A20C402B922E35", "ModMachine"),
+    ("42A36F8AEE394B98BB2E8B63CB68E3E7", "MonoDelay"),
+    ("1AF350AC983B46CAB104990A0726EAD6", "MonoToStereo"),
+    ("25B0872DB12B44B89E32ABBC1D0B3D8A", "MorphFilter"),
+    ("86DFC3F5415C40388D3AA69030C380B1", "MultibandCompressor"),
+    (
+        "F7E6BFADFCD947BEB0A726EF32CBFC70",
+        "MultibandEnvelopeShaper",
+    ),
+    ("B2FBFB2A097C40CFBAE7F15A8DAB9D2E", "MultibandExpander"),
+    ("9B646D06D6154F859591E3E87A5C5D0A", "MultiTap Delay"),
+    ("4114D8E30C024C1DB0DE375FC53CDBED", "Octaver"),
+    ("DDE3D98C0F22423AA2B32486ABEB2846", "Phaser"),
+    ("37A3AA84E3A24D069C39030EC68768E1", "PingPongDelay"),
+    ("10F9FE4142694F1EAC21E294B42577C6", "Pitch Correct"),
+    ("D849FEF360204F3EA7D907CFBD2D5631", "Quadrafuzz v2"),
+    ("143AE812D7E249D8B503B4A6E3EFC9F8", "REVelation"),
+    ("ED824AB48E0846D5959682F5626D0972", "REVerence"),
+    ("56535452494D4F72696E676D6F64756C", "RingModulator"),
+    ("56535452655641726F6F6D776F726B73", "RoomWorks"),
+    ("56535452655642726F6F6D776F726B73", "RoomWorks SE"),
+    ("54B0BB1DD40B4222BE4E876A87430F64", "Rotary"),
+    ("5653545463476E736D70746567656E65", "SMPTEGenerator"),
+    ("4995618FDDB0459E9CECF6D9A6C9A51F", "SoftClipper"),
+    ("8F59FE35CCF444FDA34C97F7B76312BB", "Squasher"),
+    ("565354535446497374657066696C7465", "StepFilter"),
+    ("001DCD3345D14A13B59DAECF75A37536", "StereoDelay"),
+    ("77BBA7CA90F14C9BB298BA9010D6DD78", "StereoEnhancer"),
+    ("8545543739404DEB84F4E6CF0DC687B5", "StudioChorus"),
+    ("946051208E29496E804F64A825C8A047", "StudioEQ"),
+    ("56535453636F336D756C746973636F70", "SuperVision"),
+    ("56535447656E327465737467656E6572", "TestGenerator"),
+    ("4D2F8E2D443844F8A12666EB2398A103", "ToneBooster"),
+    ("56535444475443747261636B636F6D70", "TrackComp"),
+    ("565354547266337472616E6365666F72", "Tranceformer"),
+    ("E97A6873690F40E986F3EE1007B5C8FC", "Tremolo"),
+    ("80BD4930EC72450BB6481AD7B1217C66", "Tube Compressor"),
+    ("6B9B08D2CA294270BF092A62865521BF", "Tuner"),
+    ("56535455564852757632326872000000", "UV22HR"),
+    ("B11C7FF1D1C04E1CB83892F669540710", "Vibrato"),
+    ("E0E5F5FC9F854334B69096445A7B2FA8", "VintageCompressor"),
+    ("918A209EFE684F8D8826FE00278A33C1", "VST AmbiConverter"),
+    ("0CDBB66985D548A9BFD8371909D24BB4", "VST AmbiDecoder"),
+    ("04F35DB10F0C47B9965EA7D63B0CCE67", "VST Amp Rack"),
+    ("406FF539A747435DAB4353448219ADC7", "VST Bass Amp"),
+    ("02968B4A891D48E88E16BC22ACFC11D8", "VST Connect CUE Mix"),
+    ("EBB43DF53F93450DA77BB4229E190E57", "VST Connect Monitor"),
+    ("0C4DFC02D57111E18CD81AB36188709B", "VST Connect SE"),
+    ("A3CA1186A3A241FEA552717108932238", "VST MultiPanner"),
+    ("A920B15DBBF04B359CB8A471C58E3B91", "VSTDynamics"),
+    ("F3092FD69524484CB663EE37D14197D2", "WahWah"),
+];
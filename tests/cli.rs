@@ -0,0 +1,94 @@
+#![allow(clippy::unwrap_used)]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use tempfile::TempDir;
+
+/// Creates a directory containing copies of the given project files from the test data.
+fn project_directory(filenames: &[&str]) -> TempDir {
+    let directory = TempDir::new().unwrap();
+    for filename in filenames {
+        fs::copy(
+            PathBuf::from("testdata").join(filename),
+            directory.path().join(filename),
+        )
+        .unwrap();
+    }
+    directory
+}
+
+/// Runs the tool with the given arguments, using an empty home directory so that no user config
+/// or cache is used.
+fn run(home_directory: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cubase-project-plugins"))
+        .args(args)
+        .env("HOME", home_directory)
+        .env("XDG_CACHE_HOME", home_directory.join(".cache"))
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_scan_csv_errors_only_on_stderr() {
+    let home_directory = TempDir::new().unwrap();
+    let directory = project_directory(&[
+        "Example Project (Cubase 14).cpr",
+        "Truncated Project (Plugin GUID).cpr",
+    ]);
+
+    let output = run(
+        home_directory.path(),
+        &[
+            "scan",
+            "--no-cache",
+            "--format",
+            "csv",
+            directory.path().to_str().unwrap(),
+        ],
+    );
+
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Truncated Project (Plugin GUID).cpr"));
+    assert!(stderr.contains("caused by:"));
+    assert!(stderr.contains("0: unable to obtain a plugin GUID"));
+
+    let mut reader = csv::Reader::from_reader(output.stdout.as_slice());
+    assert_eq!(reader.headers().unwrap().get(0), Some("path"));
+    let records = reader.records().collect::<Result<Vec<_>, _>>().unwrap();
+    assert!(!records.is_empty());
+    assert!(
+        records
+            .iter()
+            .all(|record| record[0].ends_with("Example Project (Cubase 14).cpr"))
+    );
+}
+
+/// The cache directory is only taken from the environment on Linux.
+#[cfg(target_os = "linux")]
+#[test]
+fn test_clear_cache() {
+    let home_directory = TempDir::new().unwrap();
+    let cache_path = home_directory
+        .path()
+        .join(".cache")
+        .join("cubase-project-plugins.json");
+    fs::create_dir_all(cache_path.parent().unwrap()).unwrap();
+    fs::write(&cache_path, "{}").unwrap();
+
+    let output = run(home_directory.path(), &["clear-cache"]);
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(!cache_path.exists());
+
+    let output = run(home_directory.path(), &["clear-cache"]);
+
+    assert!(output.status.success());
+}